@@ -1,11 +1,20 @@
-use avian3d::prelude::{Collider, ColliderConstructor, ColliderConstructorHierarchy, RigidBody};
+use avian3d::prelude::{Collider, ColliderConstructor, ColliderConstructorHierarchy, RigidBody, Sensor};
 use bevy::{color::palettes::tailwind, prelude::*};
+
+use platform::{move_platforms, MovingPlatform};
+use volume::{tag_volume_colliders, volume_layers, Ladder, Water};
+
+pub mod platform;
+pub mod volume;
+
 pub struct EnvironmentPlugin;
 
 impl Plugin for EnvironmentPlugin {
     fn build(&self, app: &mut App) {
         app
-        .add_systems(Startup, (spawn_world_model, spawn_lights))
+        .add_systems(Startup, (spawn_world_model, spawn_lights, spawn_platforms, spawn_ladder, spawn_water))
+        .add_systems(Update, tag_volume_colliders)
+        .add_systems(FixedUpdate, move_platforms)
         ;
     }
 }
@@ -49,6 +58,85 @@ fn spawn_world_model(
     ));
 }
 
+fn spawn_platforms(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let start = Vec3::new(4.0, 1.0, 4.0);
+
+    // slides back and forth over the level while turning slowly
+    commands.spawn((
+        Mesh3d(meshes.add(Cuboid::new(3.0, 0.5, 3.0))),
+        MeshMaterial3d(materials.add(Color::from(tailwind::SKY_500))),
+        Transform::from_translation(start),
+
+        Collider::cuboid(3.0, 0.5, 3.0),
+        RigidBody::Kinematic,
+        MovingPlatform {
+            start,
+            end: Vec3::new(4.0, 3.0, -6.0),
+            period: 10.0,
+            spin: 0.3,
+            time: 0.0,
+        },
+    ))
+    .insert(Name::new("MovingPlatform"));
+}
+
+fn spawn_ladder(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    // wall to climb
+    commands.spawn((
+        Mesh3d(meshes.add(Cuboid::new(0.5, 6.0, 2.0))),
+        MeshMaterial3d(materials.add(Color::WHITE)),
+        Transform::from_xyz(-6.0, 3.0, 0.0),
+
+        Collider::cuboid(0.5, 6.0, 2.0),
+        RigidBody::Static
+    ))
+    .insert(Name::new("LadderWall"));
+
+    // ladder volume in front of the wall, reaching a little above it so the top can be climbed over
+    commands.spawn((
+        Mesh3d(meshes.add(Cuboid::new(0.3, 6.5, 1.0))),
+        MeshMaterial3d(materials.add(Color::from(tailwind::AMBER_700))),
+        Transform::from_xyz(-5.6, 3.25, 0.0),
+
+        Collider::cuboid(0.3, 6.5, 1.0),
+        Sensor,
+        volume_layers(),
+        Ladder
+    ))
+    .insert(Name::new("Ladder"));
+}
+
+fn spawn_water(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    // deep enough to swim in, the top is the waterline
+    commands.spawn((
+        Mesh3d(meshes.add(Cuboid::new(6.0, 3.0, 6.0))),
+        MeshMaterial3d(materials.add(StandardMaterial {
+            base_color: Color::from(tailwind::BLUE_500).with_alpha(0.4),
+            alpha_mode: AlphaMode::Blend,
+            ..default()
+        })),
+        Transform::from_xyz(-8.0, 1.5, 8.0),
+
+        Collider::cuboid(6.0, 3.0, 6.0),
+        Sensor,
+        volume_layers(),
+        Water
+    ))
+    .insert(Name::new("Water"));
+}
+
 fn spawn_lights(mut commands: Commands) {
     commands.spawn((
         PointLight {
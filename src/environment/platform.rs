@@ -0,0 +1,36 @@
+use std::f32::consts::TAU;
+
+use avian3d::prelude::*;
+use bevy::prelude::*;
+
+// Kinematic platform that travels back and forth between two points and can spin around its up axis.
+// It is moved through its velocity, so players standing on it are carried along by player_move.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct MovingPlatform {
+    pub start: Vec3,
+    pub end: Vec3,
+    pub period: f32, // seconds for a round trip
+    pub spin: f32, // radians per second around the up axis
+    pub time: f32, // how far along the round trip the platform is, in seconds
+}
+
+// eases the platform along its path, the velocity is whatever reaches the next point on it this tick
+pub fn move_platforms(
+    time: Res<Time>,
+    mut query: Query<(&mut MovingPlatform, &Position, &mut LinearVelocity, &mut AngularVelocity)>
+) {
+    let delta_time = time.delta_secs();
+    if delta_time <= 0.0 {
+        return;
+    }
+
+    for (mut platform, position, mut linear_velocity, mut angular_velocity) in query.iter_mut() {
+        platform.time = (platform.time + delta_time) % platform.period;
+        let progress = 0.5 - 0.5 * f32::cos(TAU * platform.time / platform.period);
+        let target = platform.start.lerp(platform.end, progress);
+
+        linear_velocity.0 = (target - position.0) / delta_time;
+        angular_velocity.0 = Vec3::Y * platform.spin;
+    }
+}
@@ -0,0 +1,88 @@
+use avian3d::prelude::*;
+use bevy::{gltf::GltfExtras, prelude::*};
+
+// Volumes are sensor colliders that change how the player moves while it overlaps them.
+// They live on their own collision layer, so the player's ground and wall casts go straight through them.
+//
+// In glTF levels a node becomes a volume through its name ("Ladder", "Water.001", ...)
+// or its extras ({"volume": "ladder"} or {"volume": "water"}), the colliders built for its meshes are turned into sensors.
+
+#[derive(PhysicsLayer, Clone, Copy, Debug, Default)]
+pub enum GameLayer {
+    #[default]
+    Default, // everything solid, avian puts colliders here unless told otherwise
+    Volume,
+}
+
+// Climbable volume, climbing follows its local up axis
+#[derive(Component, Reflect, Default, Clone, Copy, Debug)]
+#[reflect(Component)]
+pub struct Ladder;
+
+// Fluid the player swims in, the top of the volume is the waterline
+#[derive(Component, Reflect, Default, Clone, Copy, Debug)]
+#[reflect(Component)]
+pub struct Water;
+
+/// Collision layers of a volume: only overlaps with solid colliders are of interest.
+pub fn volume_layers() -> CollisionLayers {
+    CollisionLayers::new(GameLayer::Volume, GameLayer::Default)
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum VolumeKind {
+    Ladder,
+    Water,
+}
+
+impl VolumeKind {
+    /// Reads the kind of volume from a glTF node's name or extras, extras win when both are set.
+    pub fn from_gltf(name: Option<&Name>, extras: Option<&GltfExtras>) -> Option<Self> {
+        let from_extras = extras
+            .and_then(|extras| serde_json::from_str::<serde_json::Value>(&extras.value).ok())
+            .and_then(|extras| extras.get("volume")?.as_str().and_then(Self::from_label));
+
+        from_extras.or_else(|| name.and_then(|name| Self::from_label(name.as_str().split('.').next()?)))
+    }
+
+    fn from_label(label: &str) -> Option<Self> {
+        match label.to_lowercase().as_str() {
+            "ladder" => Some(VolumeKind::Ladder),
+            "water" => Some(VolumeKind::Water),
+            _ => None,
+        }
+    }
+}
+
+// turns colliders built for glTF volume nodes (or their meshes) into sensors on the volume layer
+pub fn tag_volume_colliders(
+    mut commands: Commands,
+    colliders: Query<Entity, (Added<Collider>, Without<Ladder>, Without<Water>)>,
+    nodes: Query<(Option<&Name>, Option<&GltfExtras>, Option<&Parent>)>,
+) {
+    for collider in colliders.iter() {
+        // the collider sits on the mesh, the name and extras usually on the node above it
+        let mut current = Some(collider);
+        let mut kind = None;
+        while let Some(entity) = current {
+            let Ok((name, extras, parent)) = nodes.get(entity) else {
+                break;
+            };
+            kind = VolumeKind::from_gltf(name, extras);
+            if kind.is_some() {
+                break;
+            }
+            current = parent.map(Parent::get);
+        }
+
+        match kind {
+            Some(VolumeKind::Ladder) => {
+                commands.entity(collider).insert((Ladder, Sensor, volume_layers()));
+            },
+            Some(VolumeKind::Water) => {
+                commands.entity(collider).insert((Water, Sensor, volume_layers()));
+            },
+            None => {},
+        }
+    }
+}
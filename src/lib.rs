@@ -0,0 +1,10 @@
+use bevy::prelude::*;
+
+pub mod environment;
+pub mod player;
+pub mod constants;
+pub mod register;
+pub mod weapon;
+
+#[derive(Resource, PartialEq, Reflect)]
+pub struct CursorLocked(pub bool);
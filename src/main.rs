@@ -1,23 +1,22 @@
 use avian3d::PhysicsPlugins;
 use bevy::prelude::*;
 use bevy::window::CursorGrabMode;
-use player::component::LogicalPlayerController;
-use register::RegisterPlugin;
-use crate::environment::EnvironmentPlugin;
-use crate::player::PlayerPlugin;
-
-mod environment;
-mod player;
-mod constants;
-mod register;
+use my_crate::environment::EnvironmentPlugin;
+use my_crate::player::PlayerPlugin;
+use my_crate::player::tuning::MovementTuningPlugin;
+use my_crate::register::RegisterPlugin;
+use my_crate::weapon::WeaponPlugin;
+use my_crate::CursorLocked;
 
 fn main() {
     App::new()
         .add_plugins(DefaultPlugins)
         .add_plugins(PhysicsPlugins::default())
         .add_plugins(EnvironmentPlugin)
-        .add_plugins(PlayerPlugin)
+        .add_plugins(PlayerPlugin::default())
+        .add_plugins(WeaponPlugin)
         .add_plugins(bevy_inspector_egui::quick::WorldInspectorPlugin::new())
+        .add_plugins(MovementTuningPlugin)
         .add_plugins(RegisterPlugin)
 
         .register_type::<CursorLocked>()
@@ -27,9 +26,6 @@ fn main() {
         .run();
 }
 
-#[derive(Resource, PartialEq, Reflect)]
-pub struct CursorLocked(bool);
-
 fn manage_cursor(
     btn: Res<ButtonInput<MouseButton>>,
     key: Res<ButtonInput<KeyCode>>,
@@ -1,83 +1,267 @@
 use avian3d::{math::*, prelude::*};
 use bevy::prelude::*;
+use bevy_inspector_egui::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::constants::ANGLE_EPSILON;
+use super::controls::InputBinding;
 
 // Logical player component flag
-#[derive(Component)]
+#[derive(Component, Reflect)]
+#[reflect(Component)]
 pub struct LogicalPlayer;
 
-// Keybindings and control settings
-#[derive(Component)]
+// Keybindings and control settings, saved to the user's config file
+#[derive(Component, Reflect, InspectorOptions, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[reflect(Component, InspectorOptions)]
+#[serde(default)]
 pub struct PlayerControls {
     // put keys inside here
+    #[inspector(min = 0.0, max = 0.01, speed = 0.0001)]
     pub mouse_sensitivity:f32,
-    pub key_left: KeyCode,
-    pub key_right: KeyCode,
-    pub key_forward: KeyCode,
-    pub key_backward: KeyCode,
-    pub key_up: KeyCode,
-    pub key_down: KeyCode,
+    pub key_left: InputBinding,
+    pub key_right: InputBinding,
+    pub key_forward: InputBinding,
+    pub key_backward: InputBinding,
+    pub key_up: InputBinding,
+    pub key_down: InputBinding,
+
+    pub key_fly: InputBinding,
+    pub key_jump: InputBinding,
+    pub key_crouch: InputBinding,
+    pub key_sprint: InputBinding,
+    pub key_fire: InputBinding,
+    pub key_reload: InputBinding,
+
+    pub key_record_demo: InputBinding,
+    pub key_play_demo: InputBinding,
 
-    pub key_fly: KeyCode,
-    pub key_jump: KeyCode,
+    pub pad_fly: GamepadButton,
+    pub pad_jump: GamepadButton,
+    pub pad_crouch: GamepadButton,
+    pub pad_sprint: GamepadButton,
+    pub pad_fire: GamepadButton,
+    pub pad_reload: GamepadButton,
+
+    #[inspector(min = 0.0, max = 0.9)]
+    pub stick_move_deadzone: f32,
+    #[inspector(min = 0.0, max = 0.9)]
+    pub stick_look_deadzone: f32,
+    #[inspector(min = 0.0, max = 20.0)]
+    pub stick_look_sensitivity: f32, // radians per second at full tilt
+    #[inspector(min = 0.5, max = 4.0)]
+    pub stick_look_exponent: f32, // response curve, 1 is linear
+    #[inspector(min = 0.0, max = 4.0)]
+    pub stick_aim_acceleration: f32, // extra turn rate at full tilt, 1 doubles it, 0 disables
+    #[inspector(min = 0.0, max = 2.0)]
+    pub stick_aim_acceleration_delay: f32, // seconds at full tilt before acceleration kicks in
+    #[inspector(min = 0.0, max = 2.0)]
+    pub stick_aim_acceleration_time: f32, // seconds to ramp up to the full extra turn rate
 }
 
 impl Default for PlayerControls {
     fn default() -> Self {
         Self {
             mouse_sensitivity: 0.001,
-            key_left: KeyCode::KeyA,
-            key_right: KeyCode::KeyD,
-            key_forward: KeyCode::KeyW,
-            key_backward: KeyCode::KeyS,
-            key_up: KeyCode::KeyQ,
-            key_down: KeyCode::KeyE,
-
-            key_fly: KeyCode::KeyF,
-            key_jump: KeyCode::Space,
+            key_left: InputBinding::Key(KeyCode::KeyA),
+            key_right: InputBinding::Key(KeyCode::KeyD),
+            key_forward: InputBinding::Key(KeyCode::KeyW),
+            key_backward: InputBinding::Key(KeyCode::KeyS),
+            key_up: InputBinding::Key(KeyCode::KeyQ),
+            key_down: InputBinding::Key(KeyCode::KeyE),
+
+            key_fly: InputBinding::Key(KeyCode::KeyF),
+            key_jump: InputBinding::Key(KeyCode::Space),
+            key_crouch: InputBinding::Key(KeyCode::ControlLeft),
+            key_sprint: InputBinding::Key(KeyCode::ShiftLeft),
+            key_fire: InputBinding::Mouse(MouseButton::Left),
+            key_reload: InputBinding::Key(KeyCode::KeyR),
+
+            key_record_demo: InputBinding::Key(KeyCode::F5),
+            key_play_demo: InputBinding::Key(KeyCode::F6),
+
+            pad_fly: GamepadButton::DPadUp,
+            pad_jump: GamepadButton::South,
+            pad_crouch: GamepadButton::East,
+            pad_sprint: GamepadButton::LeftThumb,
+            pad_fire: GamepadButton::RightTrigger2,
+            pad_reload: GamepadButton::West,
+
+            stick_move_deadzone: 0.1,
+            stick_look_deadzone: 0.1,
+            stick_look_sensitivity: 4.0,
+            stick_look_exponent: 2.0,
+            stick_aim_acceleration: 1.0,
+            stick_aim_acceleration_delay: 0.25,
+            stick_aim_acceleration_time: 0.5,
         }
     }
 }
 
 // Not-raw player input
-#[derive(Component, Default)]
+// Also stored per tick in demo files, fields missing from older demos fall back to default
+#[derive(Component, Reflect, Default, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[reflect(Component)]
+#[serde(default)]
 pub struct PlayerInput {
     pub fly: bool,
     pub sprint: bool,
     pub jump: bool,
     pub crouch: bool,
+    pub fire: bool,
+    pub reload: bool,
     pub pitch: f32,
     pub yaw: f32,
     pub movement: Vec3,
 }
 
-#[derive(Component)]
+// Tuning values for the movement, also the contents of a MovementPreset asset
+#[derive(Component, Reflect, InspectorOptions, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[reflect(Component, InspectorOptions)]
+#[serde(default)]
 pub struct LogicalPlayerProperties {
+    #[inspector(min = 0.0, max = 100.0)]
     pub fly_velocity: Scalar,
+    #[inspector(min = 0.0, max = 100.0)]
     pub walk_accel: Scalar,
+    #[inspector(min = 0.0, max = 1.0)]
     pub damping_factor: Scalar,
+    #[inspector(min = 0.0, max = 30.0)]
     pub jump_impulse: Scalar,
+    #[serde(with = "super::preset::degrees")]
+    #[inspector(min = 0.0, max = std::f32::consts::FRAC_PI_2)]
     pub max_slope_angle: Scalar,
     
+    #[inspector(min = 0.0, max = 100.0)]
     pub forward_speed: f32, // is this like the maximum speed?
+    #[inspector(min = 0.0, max = 100.0)]
     pub side_speed: f32,
 
+    #[inspector(min = 0.0, max = 50.0)]
     pub crouch_speed: f32,
+    #[inspector(min = 0.0, max = 50.0)]
     pub sprint_speed: f32,
+    #[inspector(min = 0.0, max = 50.0)]
     pub walk_speed: f32,
 
-    pub traction_normal_cutoff: f32,
+    #[inspector(min = 0.5, max = 3.0)]
+    pub stand_height: f32,
+    #[inspector(min = 0.5, max = 3.0)]
+    pub crouch_height: f32,
+
+    #[inspector(min = 0.0, max = 1.0)]
+    pub max_step_height: f32,
+
+    // slopes steeper than max_slope_angle slide the player down, from min_surf_angle on they can be surfed
+    #[serde(with = "super::preset::degrees")]
+    #[inspector(min = 0.0, max = std::f32::consts::FRAC_PI_2)]
+    pub min_surf_angle: Scalar,
+
+    #[inspector(min = 0.0, max = 1.0)]
     pub friction_speed_cutoff: f32,
+    #[inspector(min = 0.0, max = 10.0)]
     pub stop_speed: f32,
     
+    #[inspector(min = 0.0, max = 30.0)]
     pub friction:f32,
+    #[inspector(min = 0.0, max = 50.0)]
     pub acceleration: f32,
 
+    #[inspector(min = 0.0, max = 60.0)]
     pub gravity:f32,
 
+    #[inspector(min = 0.0, max = 30.0)]
     pub air_speed_cap:f32,
+    #[inspector(min = 0.0, max = 200.0)]
     pub air_acceleration: f32,
+    #[inspector(min = 0.0, max = 100.0)]
     pub max_air_speed: f32,
 
+    // how much of the velocity into a surf ramp or wall is removed while airborne, 1 slides without losing speed
+    #[inspector(min = 1.0, max = 2.0)]
+    pub overbounce: f32,
+
+    #[inspector(min = 0.0, max = 20.0)]
+    pub ladder_climb_speed: f32,
+    // speed away from the ladder when jumping off it
+    #[inspector(min = 0.0, max = 20.0)]
+    pub ladder_jump_off_speed: f32,
+
+    #[inspector(min = 0.0, max = 30.0)]
+    pub swim_speed: f32,
+    #[inspector(min = 0.0, max = 50.0)]
+    pub swim_acceleration: f32,
+    // drag on the whole velocity while swimming
+    #[inspector(min = 0.0, max = 20.0)]
+    pub water_friction: f32,
+    // upward acceleration when fully submerged, the player floats where buoyancy * immersion equals gravity
+    #[inspector(min = 0.0, max = 100.0)]
+    pub buoyancy: f32,
+
+    // horizontal speed needed to start running along a wall
+    #[inspector(min = 0.0, max = 30.0)]
+    pub wall_run_min_speed: f32,
+    // fraction of gravity that applies while wall running
+    #[inspector(min = 0.0, max = 1.0)]
+    pub wall_run_gravity_scale: f32,
+    // seconds a wall run lasts before the player drops off
+    #[inspector(min = 0.0, max = 10.0)]
+    pub wall_run_max_time: f32,
+    // speed away from the wall when jumping off it
+    #[inspector(min = 0.0, max = 20.0)]
+    pub wall_jump_speed: f32,
+    #[inspector(min = 0.0, max = 20.0)]
+    pub wall_jump_impulse: f32,
+
+    // replaces friction while crouch sliding
+    #[inspector(min = 0.0, max = 30.0)]
+    pub slide_friction: f32,
+    // a slide needs this much speed to start and ends on the ground below it
+    #[inspector(min = 0.0, max = 30.0)]
+    pub slide_min_speed: f32,
+    // seconds after a slide ends before the next one can start
+    #[inspector(min = 0.0, max = 10.0)]
+    pub slide_cooldown: f32,
+
+    // how far above the top of the hull a ledge can be mantled onto
+    #[inspector(min = 0.0, max = 2.0)]
+    pub mantle_reach: f32,
+    #[inspector(min = 0.0, max = 20.0)]
+    pub mantle_speed: f32,
+
+    // seconds after walking off a ledge during which jumping still works
+    #[inspector(min = 0.0, max = 1.0)]
+    pub coyote_time: f32,
+    // seconds a jump pressed in the air is remembered, so it happens on landing
+    #[inspector(min = 0.0, max = 1.0)]
+    pub jump_buffer_time: f32,
+    // holding jump keeps jumping on every landing, without it each jump needs a new press
+    pub auto_bhop: bool,
+    // seconds after a jump during which holding it weakens gravity, extending the jump up to a capped height
+    #[inspector(min = 0.0, max = 1.0)]
+    pub jump_hold_time: f32,
+    // gravity multiplier while the jump is held within jump_hold_time
+    #[inspector(min = 0.0, max = 1.0)]
+    pub jump_hold_gravity_scale: f32,
+    // upward velocity is multiplied by this when jump is released within jump_hold_time, 1 keeps every jump the same height
+    #[inspector(min = 0.0, max = 1.0)]
+    pub jump_release_factor: f32,
+    // jumps that can be made in the air before landing again, 1 is a double jump
+    #[inspector(min = 0, max = 5)]
+    pub extra_jumps: u8,
+
+    // landing slower than this downwards does no damage
+    #[inspector(min = 0.0, max = 100.0)]
+    pub fall_damage_min_speed: f32,
+    // landing this fast or faster does fall_damage_max
+    #[inspector(min = 0.0, max = 100.0)]
+    pub fall_damage_max_speed: f32,
+    #[inspector(min = 0.0, max = 1000.0)]
+    pub fall_damage_max: f32,
+    // shape of the damage curve between the two speeds, 1 is linear, higher forgives short falls more
+    #[inspector(min = 0.1, max = 4.0)]
+    pub fall_damage_exponent: f32,
+
 }
 
 impl Default for LogicalPlayerProperties {
@@ -96,7 +280,12 @@ impl Default for LogicalPlayerProperties {
             sprint_speed: 14.0,
             walk_speed: 9.0,
 
-            traction_normal_cutoff: 0.7,
+            stand_height: 1.5,
+            crouch_height: 1.0,
+
+            max_step_height: 0.35,
+
+            min_surf_angle: (45.0 as Scalar).to_radians(),
             friction_speed_cutoff: 0.1,
             stop_speed: 1.0,
 
@@ -108,14 +297,88 @@ impl Default for LogicalPlayerProperties {
             air_speed_cap: 2.0,
             air_acceleration: 20.0,
             max_air_speed: 15.0,
+
+            overbounce: 1.0,
+
+            ladder_climb_speed: 3.0,
+            ladder_jump_off_speed: 4.0,
+
+            swim_speed: 5.0,
+            swim_acceleration: 10.0,
+            water_friction: 3.0,
+            buoyancy: 38.0,
+
+            wall_run_min_speed: 7.0,
+            wall_run_gravity_scale: 0.1,
+            wall_run_max_time: 1.5,
+            wall_jump_speed: 6.0,
+            wall_jump_impulse: 7.5,
+
+            slide_friction: 1.0,
+            slide_min_speed: 4.0,
+            slide_cooldown: 1.0,
+
+            mantle_reach: 0.5,
+            mantle_speed: 5.0,
+
+            coyote_time: 0.1,
+            jump_buffer_time: 0.1,
+            auto_bhop: true,
+            jump_hold_time: 0.0,
+            jump_hold_gravity_scale: 0.5,
+            jump_release_factor: 1.0,
+            extra_jumps: 0,
+
+            fall_damage_min_speed: 14.5,
+            fall_damage_max_speed: 26.0,
+            fall_damage_max: 100.0,
+            fall_damage_exponent: 1.0,
+        }
+    }
+}
+
+impl LogicalPlayerProperties {
+    // Classifies a surface by the angle between its normal and up
+    pub fn classify_slope(&self, normal: Vec3) -> GroundKind {
+        let slope_angle = normal.angle_between(Vec3::Y);
+        if slope_angle <= self.max_slope_angle {
+            GroundKind::Walkable
+        } else if slope_angle < self.min_surf_angle - ANGLE_EPSILON {
+            // the epsilon keeps ramps built at exactly min_surf_angle surfable despite rounding
+            GroundKind::TooSteep
+        } else {
+            GroundKind::Surfable
+        }
+    }
+
+    pub fn is_walkable(&self, normal: Vec3) -> bool {
+        self.classify_slope(normal) == GroundKind::Walkable
+    }
+
+    // Damage taken from landing with the given downward speed
+    pub fn fall_damage(&self, speed: f32) -> f32 {
+        if speed <= self.fall_damage_min_speed {
+            return 0.0;
         }
+        let range = self.fall_damage_max_speed - self.fall_damage_min_speed;
+        let fraction = if range > 0.0 { f32::min((speed - self.fall_damage_min_speed) / range, 1.0) } else { 1.0 };
+        self.fall_damage_max * fraction.powf(self.fall_damage_exponent)
+    }
+
+    // Full height of the hull while standing or crouching
+    pub fn hull_height(&self, crouched: bool) -> f32 {
+        if crouched { self.crouch_height } else { self.stand_height }
     }
 }
 
-#[derive(PartialEq)]
+#[derive(Reflect, Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
 pub enum MoveMode {
     Noclip,
     Ground,
+    Ladder, // climbing, entered by touching a Ladder volume
+    Swim, // entered by wading into Water deeper than the waist
+    WallRun, // running along a near vertical wall while airborne
+    Mantle, // climbing up onto a ledge in front of the player
 }
 
 impl Default for MoveMode {
@@ -124,19 +387,181 @@ impl Default for MoveMode {
     }
 }
 
+// How the logical player's body is simulated, switching it at runtime swaps the RigidBody
+#[derive(Component, Reflect, Default, Clone, Copy, PartialEq, Debug)]
+#[reflect(Component)]
+pub enum PlayerBody {
+    // avian resolves contacts, player_move only clips the velocity
+    #[default]
+    Dynamic,
+    // player_move sweeps and slides the hull itself and depenetrates it, no solver jitter
+    Kinematic,
+}
+
 // Contains physical state data about the logical player
 // Not to be confused with LogicalPlayerProperties that contains speed, acceleration, friction values
-#[derive(Component, Default)]
+// Also stored whole as the start of demo files, fields missing from older demos fall back to default
+#[derive(Component, Reflect, Default, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[reflect(Component)]
+#[serde(default)]
 pub struct LogicalPlayerController {
     pub move_mode: MoveMode,
     
     pub pitch: f32,
     pub yaw: f32,
     pub ground_tick: u8,
+    pub crouched: bool,
+    pub sprinting: bool,
+    // crouch sliding, started by crouching while sprinting and kept through jumps until standing up or slowing down
+    pub sliding: bool,
+    // seconds until the next slide can start
+    pub slide_cooldown: f32,
+    // horizontal velocity of the platform the player last stood on, kept in the air so jumping off keeps its momentum
+    pub base_velocity: Vec3,
+    // seconds until a ladder can be grabbed again after jumping off one
+    pub ladder_cooldown: f32,
+    // normal of the wall being run along, or of the last one until the player lands, so it can't be run along twice in a row
+    pub wall_normal: Vec3,
+    // seconds spent in the current wall run
+    pub wall_run_time: f32,
+    // where the hull ends up standing on the ledge being mantled
+    pub mantle_target: Vec3,
+    // seconds spent in the current mantle
+    pub mantle_time: f32,
+    // jump was held last tick, a press is the first tick it is held
+    pub jump_held: bool,
+    // seconds the last jump press stays buffered
+    pub jump_buffer: f32,
+    // seconds left to jump after leaving the ground
+    pub coyote_timer: f32,
+    // seconds left in which holding jump extends the current jump
+    pub jump_hold_timer: f32,
+    // jumps left in the air, refilled on the ground
+    pub extra_jumps: u8,
+    // downward speed at the end of the last tick in the air, the solver may already have stopped the body when the ground is found
+    pub fall_speed: f32,
+    // the last tick was spent in the air in the Ground move mode, finding the ground after one is a landing
+    pub airborne: bool,
+}
+
+// Sprint budget of the logical player, drained while sprinting and refilled after a delay
+#[derive(Component, Reflect, InspectorOptions)]
+#[reflect(Component, InspectorOptions)]
+pub struct Stamina {
+    pub current: f32,
+    #[inspector(min = 0.0, max = 1000.0)]
+    pub max: f32,
+    #[inspector(min = 0.0, max = 200.0)]
+    pub drain_rate: f32, // per second while sprinting
+    #[inspector(min = 0.0, max = 200.0)]
+    pub regen_rate: f32, // per second once regen_delay has passed
+    #[inspector(min = 0.0, max = 10.0)]
+    pub regen_delay: f32,
+    pub regen_timer: f32,
+    pub exhausted: bool,
+}
+
+impl Default for Stamina {
+    fn default() -> Self {
+        Self {
+            current: 100.0,
+            max: 100.0,
+            drain_rate: 25.0,
+            regen_rate: 20.0,
+            regen_delay: 1.0,
+            regen_timer: 0.0,
+            exhausted: false,
+        }
+    }
 }
 
+// Hit points of the logical player, lowered by fall damage
+#[derive(Component, Reflect, InspectorOptions)]
+#[reflect(Component, InspectorOptions)]
+pub struct Health {
+    pub current: f32,
+    #[inspector(min = 0.0, max = 1000.0)]
+    pub max: f32,
+}
+
+impl Default for Health {
+    fn default() -> Self {
+        Self {
+            current: 100.0,
+            max: 100.0,
+        }
+    }
+}
+
+impl Health {
+    pub fn is_dead(&self) -> bool {
+        self.current <= 0.0
+    }
+}
+
+#[derive(Reflect, Clone, Copy, PartialEq, Debug)]
+pub enum GroundKind {
+    Airborne,
+    Walkable,
+    TooSteep, // slides down, can't be walked up
+    Surfable,
+}
+
+impl Default for GroundKind {
+    fn default() -> Self {
+        GroundKind::Airborne
+    }
+}
+
+// What the logical player is standing on, written by player_move every tick.
+// Other systems (footsteps, animations, HUD) can read this instead of casting again.
+#[derive(Component, Reflect, Default)]
+#[reflect(Component)]
+pub struct GroundState {
+    pub kind: GroundKind,
+    pub entity: Option<Entity>,
+    pub normal: Vec3,
+    pub slope_angle: f32,
+    // velocity of the ground at the player's feet, zero for static ground
+    pub velocity: Vec3,
+}
+
+impl GroundState {
+    pub fn from_hit(hit: &ShapeHitData, player_props: &LogicalPlayerProperties) -> Self {
+        Self {
+            kind: player_props.classify_slope(hit.normal1),
+            entity: Some(hit.entity),
+            normal: hit.normal1,
+            slope_angle: hit.normal1.angle_between(Vec3::Y),
+            velocity: Vec3::ZERO,
+        }
+    }
+
+    pub fn is_grounded(&self) -> bool {
+        self.kind != GroundKind::Airborne
+    }
+}
+
+// How deep the logical player is in water, written by player_move every tick.
+// Post-processing and audio can read underwater to switch to their underwater effects.
+#[derive(Component, Reflect, Default)]
+#[reflect(Component)]
+pub struct WaterState {
+    pub immersion: f32, // 0 is dry, 1 is fully submerged
+    pub underwater: bool, // the eyes are below the waterline
+}
+
+// Logical player translation before the latest fixed tick, used to interpolate the render player between ticks
+#[derive(Component, Reflect, Default)]
+#[reflect(Component)]
+pub struct PreviousTranslation(pub Vec3);
+
 // Render player component flag and parent to LogicalPlayer entity
-#[derive(Component)]
+#[derive(Component, Reflect)]
+#[reflect(Component)]
 pub struct RenderPlayer {
     pub logical_entity: Entity,
+    // extra camera height that eases to zero, so hull size changes don't snap the view
+    pub view_offset: f32,
+    pub hull_height: f32,
 }
@@ -0,0 +1,349 @@
+use std::{fmt, fs, io, path::PathBuf};
+
+use bevy::{input::mouse::MouseWheel, prelude::*, window::PrimaryWindow};
+use bevy_inspector_egui::bevy_egui::{egui, EguiContext};
+use serde::{Deserialize, Serialize};
+
+use super::component::PlayerControls;
+use super::event::RebindEvent;
+
+// Bindings are stored in the platform config directory, e.g. ~/.config/fps_3/controls.ron on Linux
+const CONFIG_DIR_NAME: &str = "fps_3";
+const CONTROLS_FILE_NAME: &str = "controls.ron";
+
+#[derive(Debug)]
+pub enum ControlsError {
+    Io(io::Error),
+    Parse(ron::error::SpannedError),
+    Serialize(ron::Error),
+    NoConfigDir,
+}
+
+impl fmt::Display for ControlsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ControlsError::Io(error) => write!(f, "controls file error: {error}"),
+            ControlsError::Parse(error) => write!(f, "invalid controls: {error}"),
+            ControlsError::Serialize(error) => write!(f, "could not serialize controls: {error}"),
+            ControlsError::NoConfigDir => write!(f, "no config directory on this platform"),
+        }
+    }
+}
+
+impl std::error::Error for ControlsError {}
+
+impl From<io::Error> for ControlsError {
+    fn from(error: io::Error) -> Self {
+        ControlsError::Io(error)
+    }
+}
+
+// A single physical input an action can be bound to
+#[derive(Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum InputBinding {
+    Key(KeyCode),
+    Mouse(MouseButton),
+    WheelUp,
+    WheelDown,
+}
+
+impl InputBinding {
+    // wheel "presses" only last a single frame
+    pub fn is_wheel(&self) -> bool {
+        matches!(self, InputBinding::WheelUp | InputBinding::WheelDown)
+    }
+}
+
+// Every rebindable action in PlayerControls
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ControlAction {
+    Left,
+    Right,
+    Forward,
+    Backward,
+    Up,
+    Down,
+    Fly,
+    Jump,
+    Crouch,
+    Sprint,
+    Fire,
+    Reload,
+    RecordDemo,
+    PlayDemo,
+}
+
+impl ControlAction {
+    pub const ALL: [ControlAction; 14] = [
+        ControlAction::Left,
+        ControlAction::Right,
+        ControlAction::Forward,
+        ControlAction::Backward,
+        ControlAction::Up,
+        ControlAction::Down,
+        ControlAction::Fly,
+        ControlAction::Jump,
+        ControlAction::Crouch,
+        ControlAction::Sprint,
+        ControlAction::Fire,
+        ControlAction::Reload,
+        ControlAction::RecordDemo,
+        ControlAction::PlayDemo,
+    ];
+}
+
+impl PlayerControls {
+    pub fn binding(&self, action: ControlAction) -> InputBinding {
+        match action {
+            ControlAction::Left => self.key_left,
+            ControlAction::Right => self.key_right,
+            ControlAction::Forward => self.key_forward,
+            ControlAction::Backward => self.key_backward,
+            ControlAction::Up => self.key_up,
+            ControlAction::Down => self.key_down,
+            ControlAction::Fly => self.key_fly,
+            ControlAction::Jump => self.key_jump,
+            ControlAction::Crouch => self.key_crouch,
+            ControlAction::Sprint => self.key_sprint,
+            ControlAction::Fire => self.key_fire,
+            ControlAction::Reload => self.key_reload,
+            ControlAction::RecordDemo => self.key_record_demo,
+            ControlAction::PlayDemo => self.key_play_demo,
+        }
+    }
+
+    pub fn binding_mut(&mut self, action: ControlAction) -> &mut InputBinding {
+        match action {
+            ControlAction::Left => &mut self.key_left,
+            ControlAction::Right => &mut self.key_right,
+            ControlAction::Forward => &mut self.key_forward,
+            ControlAction::Backward => &mut self.key_backward,
+            ControlAction::Up => &mut self.key_up,
+            ControlAction::Down => &mut self.key_down,
+            ControlAction::Fly => &mut self.key_fly,
+            ControlAction::Jump => &mut self.key_jump,
+            ControlAction::Crouch => &mut self.key_crouch,
+            ControlAction::Sprint => &mut self.key_sprint,
+            ControlAction::Fire => &mut self.key_fire,
+            ControlAction::Reload => &mut self.key_reload,
+            ControlAction::RecordDemo => &mut self.key_record_demo,
+            ControlAction::PlayDemo => &mut self.key_play_demo,
+        }
+    }
+
+    /// Returns the other action already using `binding`, if any.
+    pub fn conflict(&self, action: ControlAction, binding: InputBinding) -> Option<ControlAction> {
+        ControlAction::ALL
+            .into_iter()
+            .find(|&other| other != action && self.binding(other) == binding)
+    }
+
+    /// Binds `action` to `binding`. An action that was already bound to it takes over the
+    /// previous binding of `action`, so nothing ends up unbound or doubly bound. Returns that action.
+    pub fn rebind(&mut self, action: ControlAction, binding: InputBinding) -> Option<ControlAction> {
+        let previous = self.binding(action);
+        let conflict = self.conflict(action, binding);
+        if let Some(other) = conflict {
+            *self.binding_mut(other) = previous;
+        }
+        *self.binding_mut(action) = binding;
+        conflict
+    }
+
+    /// All pairs of actions that share a binding, e.g. after hand-editing the config file.
+    pub fn conflicts(&self) -> Vec<(ControlAction, ControlAction)> {
+        let mut conflicts = Vec::new();
+        for (index, &action) in ControlAction::ALL.iter().enumerate() {
+            for &other in &ControlAction::ALL[index + 1..] {
+                if self.binding(action) == self.binding(other) {
+                    conflicts.push((action, other));
+                }
+            }
+        }
+        conflicts
+    }
+
+    pub fn config_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join(CONFIG_DIR_NAME).join(CONTROLS_FILE_NAME))
+    }
+
+    pub fn to_ron_string(&self) -> Result<String, ControlsError> {
+        ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default()).map_err(ControlsError::Serialize)
+    }
+
+    pub fn from_ron_str(ron: &str) -> Result<Self, ControlsError> {
+        ron::from_str(ron).map_err(ControlsError::Parse)
+    }
+
+    /// Loads the user's bindings, None if there is no config file yet.
+    pub fn load() -> Option<Result<Self, ControlsError>> {
+        let ron = fs::read_to_string(Self::config_path()?).ok()?;
+        Some(Self::from_ron_str(&ron))
+    }
+
+    pub fn save(&self) -> Result<(), ControlsError> {
+        let path = Self::config_path().ok_or(ControlsError::NoConfigDir)?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, self.to_ron_string()?)?;
+        Ok(())
+    }
+}
+
+// Snapshot of every device a binding can come from for the current frame
+pub struct BindingInput<'a> {
+    pub keys: &'a ButtonInput<KeyCode>,
+    pub mouse_buttons: &'a ButtonInput<MouseButton>,
+    pub wheel: f32, // summed vertical scroll this frame
+}
+
+impl BindingInput<'_> {
+    pub fn pressed(&self, binding: InputBinding) -> bool {
+        match binding {
+            InputBinding::Key(key) => self.keys.pressed(key),
+            InputBinding::Mouse(button) => self.mouse_buttons.pressed(button),
+            InputBinding::WheelUp => self.wheel > 0.0,
+            InputBinding::WheelDown => self.wheel < 0.0,
+        }
+    }
+
+    pub fn just_pressed(&self, binding: InputBinding) -> bool {
+        match binding {
+            InputBinding::Key(key) => self.keys.just_pressed(key),
+            InputBinding::Mouse(button) => self.mouse_buttons.just_pressed(button),
+            InputBinding::WheelUp | InputBinding::WheelDown => self.pressed(binding),
+        }
+    }
+
+    // first newly pressed input this frame, used to capture a rebinding
+    fn any_just_pressed(&self) -> Option<InputBinding> {
+        if let Some(&key) = self.keys.get_just_pressed().next() {
+            Some(InputBinding::Key(key))
+        } else if let Some(&button) = self.mouse_buttons.get_just_pressed().next() {
+            Some(InputBinding::Mouse(button))
+        } else if self.wheel > 0.0 {
+            Some(InputBinding::WheelUp)
+        } else if self.wheel < 0.0 {
+            Some(InputBinding::WheelDown)
+        } else {
+            None
+        }
+    }
+}
+
+pub fn read_wheel(wheel_events: &mut EventReader<MouseWheel>) -> f32 {
+    wheel_events.read().map(|event| event.y).sum()
+}
+
+// Insert to capture the next key, mouse button or wheel direction for the action. Escape cancels.
+#[derive(Resource)]
+pub struct Rebinding {
+    pub action: ControlAction,
+}
+
+// replaces PlayerControls with the user's config file, if there is one
+pub fn load_player_controls(
+    mut query: Query<&mut PlayerControls>
+) {
+    let Ok(mut player_controls) = query.get_single_mut() else {
+        return;
+    };
+
+    match PlayerControls::load() {
+        Some(Ok(loaded_controls)) => {
+            for (action, other) in loaded_controls.conflicts() {
+                warn!("{action:?} and {other:?} are bound to the same input");
+            }
+            *player_controls = loaded_controls;
+        },
+        Some(Err(error)) => warn!("{error}, using default controls"),
+        None => {},
+    }
+}
+
+// waits for the next input while Rebinding is present, then binds and resolves conflicts
+pub fn capture_rebinding(
+    mut commands: Commands,
+    rebinding: Option<Res<Rebinding>>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mouse_buttons: Res<ButtonInput<MouseButton>>,
+    mut wheel_events: EventReader<MouseWheel>,
+    mut rebind_events: EventWriter<RebindEvent>,
+    mut query: Query<&mut PlayerControls>
+) {
+    let wheel = read_wheel(&mut wheel_events);
+    let Some(rebinding) = rebinding else {
+        return;
+    };
+
+    let Ok(mut player_controls) = query.get_single_mut() else {
+        return;
+    };
+
+    if keys.just_pressed(KeyCode::Escape) {
+        commands.remove_resource::<Rebinding>();
+        return;
+    }
+
+    let binding_input = BindingInput { keys: &keys, mouse_buttons: &mouse_buttons, wheel };
+    let Some(binding) = binding_input.any_just_pressed() else {
+        return;
+    };
+
+    let swapped = player_controls.rebind(rebinding.action, binding);
+    rebind_events.send(RebindEvent { action: rebinding.action, binding, swapped });
+    commands.remove_resource::<Rebinding>();
+}
+
+// writes the controls to the user's config file after every rebinding
+pub fn save_player_controls(
+    mut rebind_events: EventReader<RebindEvent>,
+    query: Query<&PlayerControls>
+) {
+    if rebind_events.read().count() == 0 {
+        return;
+    }
+
+    let Ok(player_controls) = query.get_single() else {
+        return;
+    };
+
+    if let Err(error) = player_controls.save() {
+        warn!("{error}");
+    }
+}
+
+// "Controls" window listing every action with its binding, clicking one waits for the input to rebind it to
+pub fn controls_ui(
+    mut commands: Commands,
+    mut egui_contexts: Query<&mut EguiContext, With<PrimaryWindow>>,
+    rebinding: Option<Res<Rebinding>>,
+    query: Query<&PlayerControls>
+) {
+    // there is no window to show it in without the egui plugin
+    let Ok(mut egui_context) = egui_contexts.get_single_mut() else {
+        return;
+    };
+
+    let Ok(player_controls) = query.get_single() else {
+        return;
+    };
+
+    let rebinding = rebinding.map(|rebinding| rebinding.action);
+    egui::Window::new("Controls").show(egui_context.get_mut(), |ui| {
+        for action in ControlAction::ALL {
+            ui.horizontal(|ui| {
+                ui.label(format!("{action:?}"));
+                let label = if rebinding == Some(action) {
+                    "press an input, Escape cancels".to_string()
+                } else {
+                    format!("{:?}", player_controls.binding(action))
+                };
+                if ui.button(label).clicked() {
+                    commands.insert_resource(Rebinding { action });
+                }
+            });
+        }
+    });
+}
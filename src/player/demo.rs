@@ -0,0 +1,278 @@
+use std::{fmt, fs, io, path::Path};
+
+use avian3d::prelude::*;
+use bevy::{input::mouse::MouseWheel, prelude::*};
+use serde::{Deserialize, Serialize};
+
+use super::component::{LogicalPlayer, LogicalPlayerController, LogicalPlayerProperties, PlayerControls, PlayerInput, PreviousTranslation, Stamina};
+use super::controls::{read_wheel, BindingInput};
+use super::hull::PlayerHull;
+
+// Demos record the per-tick PlayerInput of the logical player together with the state it started from.
+// Playing one back feeds the recorded input into player_move instead of the live input,
+// which reproduces the run exactly as long as the level is in the same state.
+
+// Bump whenever the file layout changes in a way older demos can't be read with
+pub const DEMO_VERSION: u32 = 1;
+
+// Where the record/play keys save and load the demo
+pub const DEMO_PATH: &str = "demos/latest.ron";
+
+// The tick rate is stored as the inverse of the timestep, so it doesn't round trip exactly
+const TICK_RATE_TOLERANCE: f64 = 1e-6;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Demo {
+    pub version: u32,
+    pub tick_rate: f64,
+    pub start: DemoStart,
+    pub ticks: Vec<PlayerInput>,
+}
+
+// Logical player state at the first recorded tick
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DemoStart {
+    pub translation: Vec3,
+    pub rotation: Quat,
+    pub velocity: Vec3,
+    pub controller: LogicalPlayerController,
+
+    pub stamina: f32,
+    pub stamina_regen_timer: f32,
+    pub stamina_exhausted: bool,
+}
+
+#[derive(Debug)]
+pub enum DemoError {
+    Io(io::Error),
+    Parse(ron::error::SpannedError),
+    Serialize(ron::Error),
+    UnsupportedVersion(u32),
+    // recorded at a different tick rate than the one the player is simulated at
+    TickRateMismatch { demo: f64, expected: f64 },
+}
+
+impl fmt::Display for DemoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DemoError::Io(error) => write!(f, "demo file error: {error}"),
+            DemoError::Parse(error) => write!(f, "invalid demo: {error}"),
+            DemoError::Serialize(error) => write!(f, "could not serialize demo: {error}"),
+            DemoError::UnsupportedVersion(version) => write!(f, "demo version {version} is not supported (expected {DEMO_VERSION})"),
+            DemoError::TickRateMismatch { demo, expected } => write!(f, "demo was recorded at {demo} ticks per second, the player runs at {expected}"),
+        }
+    }
+}
+
+impl std::error::Error for DemoError {}
+
+impl From<io::Error> for DemoError {
+    fn from(error: io::Error) -> Self {
+        DemoError::Io(error)
+    }
+}
+
+impl Demo {
+    pub fn to_ron_string(&self) -> Result<String, DemoError> {
+        ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default()).map_err(DemoError::Serialize)
+    }
+
+    pub fn from_ron_str(ron: &str) -> Result<Self, DemoError> {
+        let demo: Demo = ron::from_str(ron).map_err(DemoError::Parse)?;
+        if demo.version != DEMO_VERSION {
+            return Err(DemoError::UnsupportedVersion(demo.version));
+        }
+        Ok(demo)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), DemoError> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, self.to_ron_string()?)?;
+        Ok(())
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, DemoError> {
+        Self::from_ron_str(&fs::read_to_string(path)?)
+    }
+
+    /// The same input only reproduces the run at the tick rate it was recorded at.
+    pub fn check_tick_rate(&self, tick_rate: f64) -> Result<(), DemoError> {
+        if (self.tick_rate - tick_rate).abs() > TICK_RATE_TOLERANCE {
+            return Err(DemoError::TickRateMismatch { demo: self.tick_rate, expected: tick_rate });
+        }
+        Ok(())
+    }
+}
+
+// Insert to start recording, the start state is captured on the next tick.
+// Remove it again and take the demo out to stop.
+#[derive(Resource, Default)]
+pub struct DemoRecording {
+    pub demo: Option<Demo>,
+}
+
+// Insert to play a demo back, removed automatically once every tick has been played
+#[derive(Resource)]
+pub struct DemoPlayback {
+    pub demo: Demo,
+    pub tick: usize,
+}
+
+impl DemoPlayback {
+    pub fn new(demo: Demo) -> Self {
+        Self { demo, tick: 0 }
+    }
+}
+
+// replaces the live PlayerInput with the recorded one, restoring the start state on the first tick
+pub fn play_demo(
+    mut commands: Commands,
+    fixed_time: Res<Time<Fixed>>,
+    playback: Option<ResMut<DemoPlayback>>,
+    mut query: Query<(
+        &mut Transform,
+        &mut PreviousTranslation,
+        &mut LinearVelocity,
+        &mut Collider,
+        &mut ShapeCaster,
+        &mut LogicalPlayerController,
+        &mut Stamina,
+        &mut PlayerInput,
+        &LogicalPlayerProperties,
+        &PlayerHull,
+    ), With<LogicalPlayer>>
+) {
+    let Some(mut playback) = playback else {
+        return;
+    };
+
+    let Ok((
+        mut transform,
+        mut previous_translation,
+        mut linear_velocity,
+        mut collider,
+        mut shape_caster,
+        mut logical_controller,
+        mut stamina,
+        mut player_input,
+        player_props,
+        hull,)) = query.get_single_mut() else {
+        return;
+    };
+
+    if playback.tick == 0 {
+        if let Err(error) = playback.demo.check_tick_rate(1.0 / fixed_time.timestep().as_secs_f64()) {
+            warn!("{error}");
+            commands.remove_resource::<DemoPlayback>();
+            return;
+        }
+
+        let start = &playback.demo.start;
+        transform.translation = start.translation;
+        transform.rotation = start.rotation;
+        previous_translation.0 = start.translation;
+        linear_velocity.0 = start.velocity;
+
+        *logical_controller = start.controller.clone();
+
+        hull.resize(&mut collider, &mut shape_caster, player_props.hull_height(start.controller.crouched));
+
+        stamina.current = start.stamina;
+        stamina.regen_timer = start.stamina_regen_timer;
+        stamina.exhausted = start.stamina_exhausted;
+    }
+
+    let Some(tick_input) = playback.demo.ticks.get(playback.tick) else {
+        commands.remove_resource::<DemoPlayback>();
+        return;
+    };
+
+    *player_input = tick_input.clone();
+    playback.tick += 1;
+}
+
+// appends the PlayerInput used this tick to the demo being recorded
+pub fn record_demo(
+    fixed_time: Res<Time<Fixed>>,
+    recording: Option<ResMut<DemoRecording>>,
+    query: Query<(
+        &Transform,
+        &LinearVelocity,
+        &LogicalPlayerController,
+        &Stamina,
+        &PlayerInput,
+    ), With<LogicalPlayer>>
+) {
+    let Some(mut recording) = recording else {
+        return;
+    };
+
+    let Ok((transform, linear_velocity, logical_controller, stamina, player_input)) = query.get_single() else {
+        return;
+    };
+
+    let demo = recording.demo.get_or_insert_with(|| Demo {
+        version: DEMO_VERSION,
+        tick_rate: 1.0 / fixed_time.timestep().as_secs_f64(),
+        start: DemoStart {
+            translation: transform.translation,
+            rotation: transform.rotation,
+            velocity: linear_velocity.0,
+
+            controller: logical_controller.clone(),
+
+            stamina: stamina.current,
+            stamina_regen_timer: stamina.regen_timer,
+            stamina_exhausted: stamina.exhausted,
+        },
+        ticks: Vec::new(),
+    });
+
+    demo.ticks.push(player_input.clone());
+}
+
+// toggles recording to DEMO_PATH and starts playing it back
+pub fn demo_controls(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mouse_button_input: Res<ButtonInput<MouseButton>>,
+    mut wheel_events: EventReader<MouseWheel>,
+    mut recording: Option<ResMut<DemoRecording>>,
+    playback: Option<Res<DemoPlayback>>,
+    query: Query<&PlayerControls>
+) {
+    let Ok(player_controls) = query.get_single() else {
+        return;
+    };
+
+    let binding_input = BindingInput {
+        keys: &keyboard_input,
+        mouse_buttons: &mouse_button_input,
+        wheel: read_wheel(&mut wheel_events),
+    };
+
+    if binding_input.just_pressed(player_controls.key_record_demo) {
+        if let Some(recording) = recording.as_mut() {
+            if let Some(demo) = recording.demo.take() {
+                match demo.save(DEMO_PATH) {
+                    Ok(()) => info!("saved demo with {} ticks to {DEMO_PATH}", demo.ticks.len()),
+                    Err(error) => warn!("{error}"),
+                }
+            }
+            commands.remove_resource::<DemoRecording>();
+        } else if playback.is_none() {
+            info!("recording demo");
+            commands.insert_resource(DemoRecording::default());
+        }
+    }
+
+    if binding_input.just_pressed(player_controls.key_play_demo) && recording.is_none() {
+        match Demo::load(DEMO_PATH) {
+            Ok(demo) => commands.insert_resource(DemoPlayback::new(demo)),
+            Err(error) => warn!("{error}"),
+        }
+    }
+}
@@ -0,0 +1,37 @@
+use bevy::prelude::*;
+
+use super::controls::{ControlAction, InputBinding};
+
+// Sent whenever the sprint state of a logical player changes, so HUD and audio can react
+#[derive(Event, Debug, Clone, Copy, PartialEq)]
+pub enum SprintEvent {
+    Started(Entity),
+    Stopped(Entity),
+    // stamina ran out while sprinting, sprint has to be re-pressed once stamina refills
+    Exhausted(Entity),
+}
+
+// Sent when a logical player starts or stops a crouch slide, so the camera can tilt and audio can play
+#[derive(Event, Debug, Clone, Copy, PartialEq)]
+pub enum SlideEvent {
+    Started(Entity),
+    Stopped(Entity),
+}
+
+// Sent on the tick a logical player lands on walkable ground, so fall damage, the camera, audio and particles can react
+#[derive(Event, Debug, Clone, Copy, PartialEq)]
+pub struct LandedEvent {
+    pub entity: Entity,
+    // downward speed relative to the ground when landing
+    pub speed: f32,
+    pub normal: Vec3,
+    pub ground_entity: Option<Entity>,
+}
+
+// Sent when a rebinding was captured, swapped is the action that had the binding before and took over the old one
+#[derive(Event, Debug, Clone, Copy, PartialEq)]
+pub struct RebindEvent {
+    pub action: ControlAction,
+    pub binding: InputBinding,
+    pub swapped: Option<ControlAction>,
+}
@@ -0,0 +1,98 @@
+use bevy::prelude::*;
+
+use crate::CursorLocked;
+
+use super::component::{PlayerControls, PlayerInput};
+use super::controls::Rebinding;
+use super::demo::DemoPlayback;
+
+// Stick magnitude from which the look stick counts as fully tilted for aim acceleration
+const FULL_TILT: f32 = 0.95;
+
+/// Zeroes sticks inside the circular deadzone and rescales the rest so output still starts at zero
+/// and reaches one at full tilt. The direction and relative magnitude are kept.
+pub fn apply_radial_deadzone(stick: Vec2, deadzone: f32) -> Vec2 {
+    let magnitude = stick.length();
+    if magnitude <= deadzone {
+        return Vec2::ZERO;
+    }
+    let scaled = ((magnitude - deadzone) / (1.0 - deadzone)).min(1.0);
+    stick / magnitude * scaled
+}
+
+/// Raises the stick magnitude to `exponent`, values above one give finer control near the center.
+pub fn apply_response_curve(stick: Vec2, exponent: f32) -> Vec2 {
+    let magnitude = stick.length();
+    if magnitude <= f32::EPSILON {
+        return Vec2::ZERO;
+    }
+    stick / magnitude * magnitude.powf(exponent)
+}
+
+// adds the first connected gamepad on top of the keyboard and mouse input from player_input
+// left stick keeps its magnitude so wish_speed scales, right stick turns at stick_look_sensitivity
+pub fn player_gamepad_input(
+    time: Res<Time>,
+    gamepads: Query<&Gamepad>,
+    mut query: Query<(&mut PlayerInput, &PlayerControls)>,
+    cursor_locked: Res<CursorLocked>,
+    playback: Option<Res<DemoPlayback>>,
+    rebinding: Option<Res<Rebinding>>,
+    mut full_tilt_time: Local<f32>
+) {
+    let Ok((mut player_input, player_controls)) = query.get_single_mut() else {
+        return;
+    };
+
+    if playback.is_some() || rebinding.is_some() || !cursor_locked.0 {
+        *full_tilt_time = 0.0;
+        return;
+    }
+
+    let Some(gamepad) = gamepads.iter().next() else {
+        *full_tilt_time = 0.0;
+        return;
+    };
+
+    let delta_time = time.delta_secs();
+
+    // movement
+
+    let move_stick = apply_radial_deadzone(gamepad.left_stick(), player_controls.stick_move_deadzone);
+    player_input.movement += Vec3::new(move_stick.x, 0.0, move_stick.y);
+
+    // look, with aim acceleration once the stick has been held at full tilt for a moment
+
+    let look_stick = apply_response_curve(
+        apply_radial_deadzone(gamepad.right_stick(), player_controls.stick_look_deadzone),
+        player_controls.stick_look_exponent,
+    );
+
+    if look_stick.length() >= FULL_TILT {
+        *full_tilt_time += delta_time;
+    } else {
+        *full_tilt_time = 0.0;
+    }
+
+    let ramp = if player_controls.stick_aim_acceleration_time > 0.0 {
+        ((*full_tilt_time - player_controls.stick_aim_acceleration_delay) / player_controls.stick_aim_acceleration_time).clamp(0.0, 1.0)
+    } else if *full_tilt_time > player_controls.stick_aim_acceleration_delay {
+        1.0
+    } else {
+        0.0
+    };
+    let turn_rate = player_controls.stick_look_sensitivity * (1.0 + player_controls.stick_aim_acceleration * ramp);
+
+    // same signs as the mouse: right turns to negative yaw, up looks to positive pitch
+    player_input.yaw -= look_stick.x * turn_rate * delta_time;
+    player_input.pitch += look_stick.y * turn_rate * delta_time;
+
+    // buttons
+
+    player_input.fly |= gamepad.just_pressed(player_controls.pad_fly);
+    player_input.jump |= gamepad.pressed(player_controls.pad_jump);
+    player_input.crouch |= gamepad.pressed(player_controls.pad_crouch);
+    player_input.sprint |= gamepad.pressed(player_controls.pad_sprint);
+    player_input.fire |= gamepad.pressed(player_controls.pad_fire);
+    player_input.reload |= gamepad.pressed(player_controls.pad_reload);
+}
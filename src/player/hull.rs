@@ -0,0 +1,156 @@
+use std::fmt;
+
+use avian3d::{math::*, prelude::*};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::component::{LogicalPlayerController, LogicalPlayerProperties};
+
+// Distance from the top of the hull down to the eyes
+const EYE_BELOW_TOP: f32 = 0.5;
+
+// The shape caster is slightly smaller than the collider, so it doesn't report what the collider rests against
+const CASTER_SCALE: Scalar = 0.99;
+
+// Shape of the logical player's collider. The height isn't part of it, that follows
+// stand_height and crouch_height, so every hull is centered on the transform with its feet half a height below.
+#[derive(Component, Reflect, Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+#[reflect(Component)]
+pub enum PlayerHull {
+    Cylinder { radius: f32 },
+    // rounded bottom rolls over stair edges and hull seams instead of catching on them
+    Capsule { radius: f32 },
+    // square footprint, half_extent is the distance from the center to each side
+    Box { half_extent: f32 },
+}
+
+impl Default for PlayerHull {
+    fn default() -> Self {
+        PlayerHull::Cylinder { radius: 0.5 }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum HullError {
+    InvalidSize(f32),
+    InvalidHeight(f32),
+    TooShortForCapsule { height: f32, radius: f32 },
+}
+
+impl fmt::Display for HullError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HullError::InvalidSize(size) => write!(f, "player hull size must be positive, got {size}"),
+            HullError::InvalidHeight(height) => write!(f, "player hull height must be positive, got {height}"),
+            HullError::TooShortForCapsule { height, radius } => write!(f, "a capsule hull with radius {radius} needs a height of at least {}, got {height}", 2.0 * radius),
+        }
+    }
+}
+
+impl std::error::Error for HullError {}
+
+impl PlayerHull {
+    /// Horizontal distance from the center to the side of the hull.
+    pub fn radius(&self) -> f32 {
+        match *self {
+            PlayerHull::Cylinder { radius } | PlayerHull::Capsule { radius } => radius,
+            PlayerHull::Box { half_extent } => half_extent,
+        }
+    }
+
+    fn with_radius(&self, radius: f32) -> Self {
+        match *self {
+            PlayerHull::Cylinder { .. } => PlayerHull::Cylinder { radius },
+            PlayerHull::Capsule { .. } => PlayerHull::Capsule { radius },
+            PlayerHull::Box { .. } => PlayerHull::Box { half_extent: radius },
+        }
+    }
+
+    /// Collider with the given full height.
+    pub fn collider(&self, height: f32) -> Collider {
+        match *self {
+            PlayerHull::Cylinder { radius } => Collider::cylinder(radius, height),
+            PlayerHull::Capsule { radius } => Collider::capsule(radius, (height - 2.0 * radius).max(0.0)),
+            PlayerHull::Box { half_extent } => Collider::cuboid(2.0 * half_extent, height, 2.0 * half_extent),
+        }
+    }
+
+    /// Collider that is scaled laterally (XZ plane) but keeps its full height,
+    /// so ground and ceiling probes don't catch the walls the player is pressed against.
+    pub fn scaled_laterally(&self, height: f32, scale: f32) -> Collider {
+        self.with_radius(self.radius() * scale).collider(height)
+    }
+
+    /// Offset from the center of the player transform down to the feet.
+    pub fn foot_offset(&self, height: f32) -> f32 {
+        height / 2.0
+    }
+
+    /// Offset from the center of the player transform up to the eyes.
+    pub fn eye_offset(&self, height: f32) -> f32 {
+        height / 2.0 - EYE_BELOW_TOP
+    }
+
+    /// Checks that the hull can be built at both the standing and crouching height.
+    pub fn validate(&self, player_props: &LogicalPlayerProperties) -> Result<(), HullError> {
+        let radius = self.radius();
+        if !(radius > 0.0 && radius.is_finite()) {
+            return Err(HullError::InvalidSize(radius));
+        }
+
+        for height in [player_props.stand_height, player_props.crouch_height] {
+            if !(height > 0.0 && height.is_finite()) {
+                return Err(HullError::InvalidHeight(height));
+            }
+            if matches!(self, PlayerHull::Capsule { .. }) && height < 2.0 * radius {
+                return Err(HullError::TooShortForCapsule { height, radius });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Rebuilds the collider (and the shape caster that follows it) at the given full height.
+    pub fn resize(&self, collider: &mut Collider, shape_caster: &mut ShapeCaster, height: f32) {
+        *collider = self.collider(height);
+        shape_caster.shape = self.caster_shape(height);
+    }
+
+    pub fn caster_shape(&self, height: f32) -> Collider {
+        let mut caster_shape = self.collider(height);
+        caster_shape.set_scale(Vector::ONE * CASTER_SCALE, 10);
+        caster_shape
+    }
+}
+
+// Last PlayerHull that passed validation, an invalid PlayerHull is reverted to it
+#[derive(Component, Clone, Copy, Debug, Default)]
+pub struct ValidPlayerHull(pub PlayerHull);
+
+// Hull the logical player is spawned with, replaced by the default hull if it doesn't validate
+#[derive(Resource, Clone, Copy, Debug, Default)]
+pub struct SpawnPlayerHull(pub PlayerHull);
+
+// rebuilds the collider when PlayerHull is changed (e.g. in the inspector), invalid hulls are reported and reverted
+pub fn apply_player_hull(
+    mut query: Query<(
+        &mut PlayerHull,
+        &mut ValidPlayerHull,
+        &LogicalPlayerProperties,
+        &LogicalPlayerController,
+        &mut Collider,
+        &mut ShapeCaster,
+    ), Changed<PlayerHull>>
+) {
+    for (mut hull, mut valid_hull, player_props, logical_controller, mut collider, mut shape_caster) in query.iter_mut() {
+        if let Err(error) = hull.validate(player_props) {
+            warn!("{error}, keeping the previous hull");
+            // crouching and everything else resizing the collider reads PlayerHull, so it can't keep the invalid one
+            *hull.bypass_change_detection() = valid_hull.0;
+            continue;
+        }
+
+        valid_hull.0 = *hull;
+        hull.resize(&mut collider, &mut shape_caster, player_props.hull_height(logical_controller.crouched));
+    }
+}
@@ -1,61 +1,152 @@
-use avian3d::{math::{Quaternion, Vector}, prelude::{CoefficientCombine, Collider, Friction, GravityScale, LockedAxes, Mass, Restitution, RigidBody, ShapeCaster, SleepingDisabled}};
-use bevy::{prelude::*};
+use avian3d::{math::{Quaternion, Vector}, prelude::{CoefficientCombine, Friction, GravityScale, LockedAxes, Mass, Restitution, RigidBody, ShapeCaster, SleepingDisabled}};
+use bevy::{input::InputSystem, prelude::*};
 
-use component::{LogicalPlayer, LogicalPlayerController, LogicalPlayerProperties, PlayerControls, PlayerInput, RenderPlayer};
-use system::{player_input, player_look, player_move, player_render};
+use component::{GroundState, Health, LogicalPlayer, LogicalPlayerController, LogicalPlayerProperties, PlayerBody, PlayerControls, PlayerInput, PreviousTranslation, RenderPlayer, Stamina, WaterState};
+use controls::{capture_rebinding, controls_ui, load_player_controls, save_player_controls};
+use demo::{demo_controls, play_demo, record_demo};
+use gamepad::player_gamepad_input;
+use hull::{apply_player_hull, PlayerHull, SpawnPlayerHull, ValidPlayerHull};
+use preset::{apply_movement_presets, MovementPreset, MovementPresetHandle, MovementPresetLoader, SpawnMovementPreset};
+use event::{LandedEvent, RebindEvent, SlideEvent, SprintEvent};
+use system::{apply_player_body, consume_tick_input, player_fall_damage, player_input, player_look, player_move, player_render, player_sprint, store_previous_translation};
 
 pub mod system;
 pub mod component;
+pub mod event;
+pub mod movement;
+pub mod demo;
+pub mod controls;
+pub mod gamepad;
+pub mod hull;
+pub mod preset;
+pub mod tuning;
 
-pub struct PlayerPlugin;
+pub struct PlayerPlugin {
+    // movement simulation rate in ticks per second, physics steps at the same rate
+    pub tick_rate: f64,
+    // skips keyboard/mouse input and the camera, so the controller can run without a window (tests, servers)
+    pub headless: bool,
+    // name of the preset in assets/presets the player spawns with, None keeps LogicalPlayerProperties::default()
+    pub movement_preset: Option<String>,
+    // collider shape of the logical player
+    pub hull: PlayerHull,
+}
+
+impl Default for PlayerPlugin {
+    fn default() -> Self {
+        Self {
+            tick_rate: 64.0,
+            headless: false,
+            movement_preset: Some("source".to_string()),
+            hull: PlayerHull::default(),
+        }
+    }
+}
 
 impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
         app
+        .insert_resource(Time::<Fixed>::from_hz(self.tick_rate))
+        .add_event::<SprintEvent>()
+        .add_event::<SlideEvent>()
+        .add_event::<LandedEvent>()
+        .add_event::<RebindEvent>()
+        .init_asset::<MovementPreset>()
+        .init_asset_loader::<MovementPresetLoader>()
+        .insert_resource(SpawnPlayerHull(self.hull))
         .add_systems(Startup, spawn_player)
-        .add_systems(PreUpdate, (player_input, player_look,
-            player_move, player_render
+        .add_systems(Update, apply_movement_presets)
+        .add_systems(FixedUpdate, (apply_player_body, apply_player_hull, store_previous_translation, play_demo, record_demo, player_look, player_sprint,
+            player_move, player_fall_damage, consume_tick_input
             ).chain()
         )
         ;
+
+        if let Some(movement_preset) = &self.movement_preset {
+            app.insert_resource(SpawnMovementPreset(movement_preset.clone()));
+        }
+
+        if !self.headless {
+            app
+            .add_systems(Startup, (spawn_render_player, load_player_controls).after(spawn_player))
+            .add_systems(PreUpdate, (capture_rebinding, player_input, player_gamepad_input).chain().after(InputSystem))
+            .add_systems(Update, (player_render, demo_controls, save_player_controls, controls_ui))
+            ;
+        }
     }
 }
 
 fn spawn_player(
-    mut commands: Commands
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    spawn_preset: Option<Res<SpawnMovementPreset>>,
+    spawn_hull: Res<SpawnPlayerHull>
 ) {
-    let height = 3.0;
-    let collider = Collider::cylinder(0.5, height / 2.0);
-    let mut caster_shape = collider.clone();
-    caster_shape.set_scale(Vector::ONE * 0.99, 10);
+    let player_props = LogicalPlayerProperties::default();
+    let spawn_point = Vec3::new(0.0, 4.0, 0.0);
+
+    let hull = match spawn_hull.0.validate(&player_props) {
+        Ok(()) => spawn_hull.0,
+        Err(error) => {
+            warn!("{error}, spawning with the default hull instead");
+            PlayerHull::default()
+        },
+    };
+    let height = player_props.stand_height;
+    let collider = hull.collider(height);
+    let caster_shape = hull.caster_shape(height);
 
     // logical player entity
-    let logical_player = commands.spawn((
-        Transform::from_xyz(0.0, 4.0, 0.0),
-        LogicalPlayer,
-        LogicalPlayerProperties::default(),
-        LogicalPlayerController::default(),
-        PlayerControls::default(),
-        PlayerInput::default(),
-        
-        RigidBody::Dynamic,
-        collider,
-        GravityScale(0.0), // gravity is handled in player_move(), so surfing is supported.
-        ShapeCaster::new(
-            caster_shape,
-            Vector::ZERO,
-            Quaternion::default(),
-            Dir3::NEG_Y,
-        ).with_max_distance(0.2),
-        LockedAxes::ROTATION_LOCKED,
-        SleepingDisabled,
-        Friction::ZERO.with_combine_rule(CoefficientCombine::Min),
-        Restitution::ZERO.with_combine_rule(CoefficientCombine::Min),
-        Mass(1.0)
-        
-    ))
-    .insert(Name::new("LogicalPlayer"))
-    .id();
+    let mut logical_player = commands.spawn((
+        (
+            Transform::from_translation(spawn_point),
+            LogicalPlayer,
+            player_props,
+            LogicalPlayerController::default(),
+            PlayerBody::default(),
+            hull,
+            ValidPlayerHull(hull),
+            PlayerControls::default(),
+            PlayerInput::default(),
+            Stamina::default(),
+            Health::default(),
+            GroundState::default(),
+            WaterState::default(),
+            PreviousTranslation(spawn_point),
+        ),
+        // bundles are limited to 15 items, so the physics components are grouped
+        (
+            RigidBody::Dynamic,
+            collider,
+            GravityScale(0.0), // gravity is handled in player_move(), so surfing is supported.
+            ShapeCaster::new(
+                caster_shape,
+                Vector::ZERO,
+                Quaternion::default(),
+                Dir3::NEG_Y,
+            ).with_max_distance(0.2),
+            LockedAxes::ROTATION_LOCKED,
+            SleepingDisabled,
+            Friction::ZERO.with_combine_rule(CoefficientCombine::Min),
+            Restitution::ZERO.with_combine_rule(CoefficientCombine::Min),
+            Mass(1.0)
+        ),
+    ));
+    logical_player.insert(Name::new("LogicalPlayer"));
+
+    // the defaults above stay in place until the preset has loaded
+    if let Some(spawn_preset) = spawn_preset {
+        logical_player.insert(MovementPresetHandle(asset_server.load(MovementPreset::path(&spawn_preset.0))));
+    }
+}
+
+fn spawn_render_player(
+    mut commands: Commands,
+    logical_query: Query<(Entity, &LogicalPlayerProperties), With<LogicalPlayer>>
+) {
+    let Ok((logical_player, player_props)) = logical_query.get_single() else {
+        return;
+    };
 
     // render player entity
     commands.spawn((
@@ -65,7 +156,7 @@ fn spawn_player(
             ..default()
         }),
         Transform::from_xyz(0.0, 4.0, 10.0).looking_at(Vec3::ZERO, Vec3::Y),
-        RenderPlayer { logical_entity: logical_player }
+        RenderPlayer { logical_entity: logical_player, view_offset: 0.0, hull_height: player_props.stand_height }
     ))
     .insert(Name::new("RenderPlayer"));
 }
\ No newline at end of file
@@ -0,0 +1,324 @@
+use bevy::math::{EulerRot, Mat3, Vec3, Vec3Swizzles};
+
+use super::component::{GroundKind, LogicalPlayerProperties};
+
+// Pure Source-style movement math, kept free of systems and queries so it can be unit tested
+// and run on a server. player_move gathers the inputs from the ECS and writes the result back.
+
+// Physical state that carries over from one tick to the next
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct MovementState {
+    pub velocity: Vec3,
+    pub ground_tick: u8,
+}
+
+// Everything the player wants to do this tick
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct MovementInput {
+    pub movement: Vec3, // local space, x is right and z is forward, same as PlayerInput::movement
+    pub yaw: f32,
+    pub pitch: f32,
+    pub jump: bool,
+    pub crouched: bool,
+    pub sprinting: bool,
+    pub sliding: bool,
+}
+
+// Looking further down than this while pressing forward climbs down a ladder
+const LADDER_DESCEND_PITCH: f32 = -0.5;
+
+// Speed towards the ladder while climbing forward, carries the player over the top onto the ledge
+const LADDER_PUSH_SPEED: f32 = 1.5;
+
+// Speed towards the wall while wall running, keeps the hull in contact with it
+const WALL_STICK_SPEED: f32 = 0.5;
+
+// The ladder the player is climbing
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LadderHit {
+    pub axis: Vec3, // unit length, pointing up the ladder
+    pub outward: Vec3, // unit length, horizontal and pointing from the ladder to the player
+}
+
+// What the ground probe found below the player this tick
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GroundHit {
+    pub normal: Vec3,
+}
+
+impl MovementInput {
+    /// Returns the world space wish direction (unit length or zero) and the wish speed clamped to the current max speed.
+    /// Analog input below full tilt lowers the max speed proportionally.
+    pub fn wish(&self, player_props: &LogicalPlayerProperties) -> (Vec3, f32) {
+        let speeds = Vec3::new(player_props.side_speed, 0.0, player_props.forward_speed);
+        let mut move_to_world = Mat3::from_axis_angle(Vec3::Y, self.yaw);
+        move_to_world.z_axis *= -1.0; // Forward is -Z
+        let mut wish_direction = move_to_world * (self.movement * speeds);
+        let wish_speed = wish_direction.length();
+
+        if wish_speed > f32::EPSILON {
+            // avoid division by zero
+            wish_direction /= wish_speed; // effectively normalizes to unit circle, avoiding length computation twice
+        }
+
+        let max_speed = if self.crouched {
+            player_props.crouch_speed
+        } else if self.sprinting {
+            player_props.sprint_speed
+        } else {
+            player_props.walk_speed
+        };
+        let analog_scale = f32::min(self.movement.xz().length(), 1.0);
+
+        (wish_direction, f32::min(wish_speed, max_speed * analog_scale))
+    }
+}
+
+impl MovementState {
+    /// Advances the movement by one tick of length `dt`.
+    pub fn step(
+        &self,
+        input: &MovementInput,
+        player_props: &LogicalPlayerProperties,
+        ground_hit: Option<GroundHit>,
+        dt: f32,
+    ) -> MovementState {
+        let (wish_direction, wish_speed) = input.wish(player_props);
+        let mut velocity = self.velocity;
+
+        let (ground_kind, ground_normal) = match ground_hit {
+            Some(hit) => (player_props.classify_slope(hit.normal), hit.normal),
+            None => (GroundKind::Airborne, Vec3::ZERO),
+        };
+
+        match ground_kind {
+            GroundKind::Walkable => {
+                // only apply friction after at least one tick, allows b-hopping (and slide jumping) without losing speed
+                if self.ground_tick >= 1 {
+                    let friction = if input.sliding { player_props.slide_friction } else { player_props.friction };
+                    velocity = apply_friction(velocity, friction, player_props, dt);
+                }
+
+                if input.sliding {
+                    // no walking while sliding, slopes speed the slide up going down and slow it going up
+                    velocity -= project_onto_plane(Vec3::Y, ground_normal) * player_props.gravity * dt;
+                } else {
+                    velocity += accelerate(wish_direction, wish_speed, player_props.acceleration, velocity, dt);
+                }
+                velocity = project_onto_plane(velocity, ground_normal);
+
+                if input.jump {
+                    velocity.y += player_props.jump_impulse;
+                }
+
+                MovementState {
+                    velocity,
+                    // Increment ground tick but cap at max value
+                    ground_tick: self.ground_tick.saturating_add(1),
+                }
+            },
+            GroundKind::TooSteep => {
+                // sliding down, no friction and no walking up the slope
+                let downhill = Vec3::new(ground_normal.x, 0.0, ground_normal.z).normalize_or_zero();
+                let uphill_amount = f32::min(Vec3::dot(wish_direction, downhill), 0.0);
+                let wish_direction = (wish_direction - uphill_amount * downhill).normalize_or_zero();
+
+                let mut add = accelerate(wish_direction, wish_speed, player_props.acceleration, velocity, dt);
+                add.y -= player_props.gravity * dt;
+                velocity += add;
+
+                MovementState {
+                    velocity: clip_into_plane(velocity, ground_normal),
+                    ground_tick: 0,
+                }
+            },
+            GroundKind::Surfable | GroundKind::Airborne => {
+                // airborne, surfing is air movement that can't go into the ramp
+                let wish_speed = f32::min(wish_speed, player_props.air_speed_cap);
+
+                let mut add = accelerate(wish_direction, wish_speed, player_props.air_acceleration, velocity, dt);
+                add.y = -player_props.gravity * dt;
+                velocity += add;
+
+                if ground_kind == GroundKind::Surfable {
+                    velocity = clip_velocity(velocity, ground_normal, player_props.overbounce);
+                }
+
+                MovementState {
+                    velocity: cap_air_speed(velocity, player_props.max_air_speed),
+                    ground_tick: 0,
+                }
+            },
+        }
+    }
+}
+
+/// Velocity while climbing a ladder. Forward climbs up, or down when looking down, and strafing moves sideways along it.
+/// Gravity doesn't apply, letting go of the keys stops the player in place.
+pub fn ladder_velocity(input: &MovementInput, player_props: &LogicalPlayerProperties, ladder: &LadderHit) -> Vec3 {
+    if input.jump {
+        return ladder.outward * player_props.ladder_jump_off_speed;
+    }
+
+    let climb_direction = if input.pitch < LADDER_DESCEND_PITCH { -1.0 } else { 1.0 };
+    let climb = input.movement.z * climb_direction;
+
+    let right = Mat3::from_axis_angle(Vec3::Y, input.yaw) * Vec3::X;
+    let side = (right - Vec3::dot(right, ladder.axis) * ladder.axis - Vec3::dot(right, ladder.outward) * ladder.outward).normalize_or_zero();
+    let strafe = input.movement.x;
+
+    let mut velocity = (ladder.axis * climb + side * strafe).clamp_length_max(1.0) * player_props.ladder_climb_speed;
+    if input.movement.z > 0.0 {
+        velocity -= ladder.outward * LADDER_PUSH_SPEED;
+    }
+    velocity
+}
+
+/// Velocity while swimming: moves along the view like noclip, with drag and buoyancy.
+/// `immersion` is how much of the hull is under water, near the surface buoyancy and gravity cancel out and the player bobs.
+pub fn swim_velocity(
+    velocity: Vec3,
+    input: &MovementInput,
+    player_props: &LogicalPlayerProperties,
+    immersion: f32,
+    dt: f32,
+) -> Vec3 {
+    let mut move_to_world = Mat3::from_euler(EulerRot::YXZ, input.yaw, input.pitch, 0.0);
+    move_to_world.z_axis *= -1.0; // Forward is -Z
+    move_to_world.y_axis = Vec3::Y; // Up is Y
+    let mut wish_velocity = move_to_world * input.movement;
+    if input.jump {
+        wish_velocity.y += 1.0;
+    }
+    let wish_speed = f32::min(wish_velocity.length(), 1.0) * player_props.swim_speed;
+    let wish_direction = wish_velocity.normalize_or_zero();
+
+    let mut velocity = velocity;
+    let speed = velocity.length();
+    if speed > 0.0 {
+        let drop = speed * player_props.water_friction * dt;
+        velocity *= f32::max(speed - drop, 0.0) / speed;
+    }
+
+    velocity += accelerate(wish_direction, wish_speed, player_props.swim_acceleration, velocity, dt);
+    velocity.y += (player_props.buoyancy * immersion - player_props.gravity) * dt;
+    velocity
+}
+
+/// Velocity while running along a wall: what goes into or away from the wall is removed and gravity is scaled down.
+/// Jumping kicks off along the wall normal.
+pub fn wall_run_velocity(
+    velocity: Vec3,
+    wall_normal: Vec3,
+    jump: bool,
+    player_props: &LogicalPlayerProperties,
+    dt: f32,
+) -> Vec3 {
+    let mut velocity = project_onto_plane(velocity, wall_normal);
+
+    if jump {
+        velocity += wall_normal * player_props.wall_jump_speed;
+        velocity.y = player_props.wall_jump_impulse;
+        return velocity;
+    }
+
+    velocity.y -= player_props.gravity * player_props.wall_run_gravity_scale * dt;
+    velocity - wall_normal * WALL_STICK_SPEED
+}
+
+/// Source-style ground friction on the lateral (XZ) velocity.
+pub fn apply_friction(velocity: Vec3, friction: f32, player_props: &LogicalPlayerProperties, dt: f32) -> Vec3 {
+    let lateral_speed = velocity.xz().length();
+    if lateral_speed > player_props.friction_speed_cutoff {
+        let control = f32::max(lateral_speed, player_props.stop_speed);
+        let drop = control * friction * dt;
+        let new_speed = f32::max((lateral_speed - drop) / lateral_speed, 0.0);
+        Vec3::new(velocity.x * new_speed, velocity.y, velocity.z * new_speed)
+    } else {
+        Vec3::ZERO
+    }
+}
+
+/// Returns the velocity to add so the speed along `wish_direction` approaches `wish_speed`.
+pub fn accelerate(
+    wish_direction: Vec3,
+    wish_speed: f32,
+    acceleration: f32,
+    velocity: Vec3,
+    dt: f32,
+) -> Vec3 {
+    let velocity_projection = Vec3::dot(velocity, wish_direction);
+    let add_speed = wish_speed - velocity_projection;
+    if add_speed <= 0.0 {
+        return Vec3::ZERO;
+    }
+
+    let acceleration_speed = f32::min(acceleration * wish_speed * dt, add_speed);
+    wish_direction * acceleration_speed
+}
+
+/// Removes all velocity along the plane normal, so the player moves along the ground.
+pub fn project_onto_plane(velocity: Vec3, normal: Vec3) -> Vec3 {
+    // (how much current velocity aligns with hit_normal) * in the direction of hit_normal.
+    velocity - Vec3::dot(velocity, normal) * normal
+}
+
+/// Removes the part of the velocity that points into a plane, keeping the part that slides along it.
+pub fn clip_into_plane(velocity: Vec3, normal: Vec3) -> Vec3 {
+    let into_plane = f32::min(Vec3::dot(velocity, normal), 0.0);
+    velocity - into_plane * normal
+}
+
+/// Source's ClipVelocity: removes the velocity going into the plane, an overbounce above 1 pushes off it slightly.
+pub fn clip_velocity(velocity: Vec3, normal: Vec3, overbounce: f32) -> Vec3 {
+    let backoff = Vec3::dot(velocity, normal) * overbounce;
+    let mut clipped = velocity - backoff * normal;
+
+    // rounding must not leave any velocity going into the plane
+    let adjust = Vec3::dot(clipped, normal);
+    if adjust < 0.0 {
+        clipped -= adjust * normal;
+    }
+    clipped
+}
+
+/// Clips the velocity against every plane it moves into, like Source's TryPlayerMove.
+/// Wedged between two planes it slides along their crease, between more it stops.
+pub fn clip_velocity_planes(velocity: Vec3, planes: &[Vec3], overbounce: f32) -> Vec3 {
+    if planes.iter().all(|&plane| Vec3::dot(velocity, plane) >= 0.0) {
+        return velocity;
+    }
+
+    for (index, &plane) in planes.iter().enumerate() {
+        if Vec3::dot(velocity, plane) >= 0.0 {
+            continue;
+        }
+
+        let clipped = clip_velocity(velocity, plane, overbounce);
+        let into_other_plane = planes
+            .iter()
+            .enumerate()
+            .any(|(other_index, &other)| other_index != index && Vec3::dot(clipped, other) < 0.0);
+        if !into_other_plane {
+            return clipped;
+        }
+    }
+
+    if let [first, second] = planes {
+        let crease = Vec3::cross(*first, *second).normalize_or_zero();
+        crease * Vec3::dot(crease, velocity)
+    } else {
+        Vec3::ZERO
+    }
+}
+
+/// Scales the lateral (XZ) velocity down to `max_air_speed`, leaving vertical velocity alone.
+pub fn cap_air_speed(velocity: Vec3, max_air_speed: f32) -> Vec3 {
+    let air_speed = velocity.xz().length();
+    if air_speed > max_air_speed {
+        let ratio = max_air_speed / air_speed;
+        Vec3::new(velocity.x * ratio, velocity.y, velocity.z * ratio)
+    } else {
+        velocity
+    }
+}
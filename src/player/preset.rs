@@ -0,0 +1,175 @@
+use std::{fmt, fs, io, path::PathBuf};
+
+use avian3d::prelude::*;
+use bevy::{asset::{io::{file::FileAssetReader, Reader}, AssetLoader, LoadContext}, prelude::*};
+use serde::{Deserialize, Serialize};
+
+use super::component::{LogicalPlayer, LogicalPlayerController, LogicalPlayerProperties};
+use super::hull::PlayerHull;
+
+// Movement presets are LogicalPlayerProperties stored as RON files under assets/presets,
+// e.g. assets/presets/quake.preset.ron. Fields left out of a file keep their default value
+// and angles are written in degrees. Edited files are reapplied to the live player when
+// the asset server watches for changes (the dev feature turns that on).
+
+pub const PRESET_DIR: &str = "presets";
+pub const PRESET_EXTENSION: &str = "preset.ron";
+
+// Presets shipped in assets/presets
+pub const BUILTIN_PRESETS: [&str; 4] = ["source", "quake", "arcade", "realistic"];
+
+#[derive(Asset, TypePath, Clone, Debug, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct MovementPreset {
+    pub properties: LogicalPlayerProperties,
+}
+
+impl MovementPreset {
+    /// Asset path of the preset called `name`, e.g. "source" -> "presets/source.preset.ron".
+    pub fn path(name: &str) -> String {
+        format!("{PRESET_DIR}/{name}.{PRESET_EXTENSION}")
+    }
+
+    /// Where the preset called `name` lives on disk, inside the asset folder the AssetServer reads from.
+    pub fn file_path(name: &str) -> PathBuf {
+        FileAssetReader::new(AssetPlugin::DEFAULT_UNPROCESSED_FILE_PATH).root_path().join(Self::path(name))
+    }
+
+    pub fn to_ron_string(&self) -> Result<String, MovementPresetError> {
+        ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default()).map_err(MovementPresetError::Serialize)
+    }
+
+    /// Writes the preset to its file, a running game with hot reload then picks it up like any other edit.
+    pub fn save(&self, name: &str) -> Result<PathBuf, MovementPresetError> {
+        let path = Self::file_path(name);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, self.to_ron_string()?)?;
+        Ok(path)
+    }
+}
+
+#[derive(Debug)]
+pub enum MovementPresetError {
+    Io(io::Error),
+    Parse(ron::error::SpannedError),
+    Serialize(ron::Error),
+}
+
+impl fmt::Display for MovementPresetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MovementPresetError::Io(error) => write!(f, "could not read movement preset: {error}"),
+            MovementPresetError::Parse(error) => write!(f, "invalid movement preset: {error}"),
+            MovementPresetError::Serialize(error) => write!(f, "could not serialize movement preset: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for MovementPresetError {}
+
+impl From<io::Error> for MovementPresetError {
+    fn from(error: io::Error) -> Self {
+        MovementPresetError::Io(error)
+    }
+}
+
+#[derive(Default)]
+pub struct MovementPresetLoader;
+
+impl AssetLoader for MovementPresetLoader {
+    type Asset = MovementPreset;
+    type Settings = ();
+    type Error = MovementPresetError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        ron::de::from_bytes(&bytes).map_err(MovementPresetError::Parse)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &[PRESET_EXTENSION]
+    }
+}
+
+// Name of the preset the logical player is spawned with, LogicalPlayerProperties::default() is used without it
+#[derive(Resource, Clone, Debug)]
+pub struct SpawnMovementPreset(pub String);
+
+// The preset a player follows, its properties are replaced whenever the preset (re)loads or the handle changes
+#[derive(Component, Reflect, Clone, Debug)]
+#[reflect(Component)]
+pub struct MovementPresetHandle(pub Handle<MovementPreset>);
+
+// copies loaded and modified presets into LogicalPlayerProperties, resizing the hull if its heights changed
+// presets the hull can't be built with are reported and skipped
+pub fn apply_movement_presets(
+    mut asset_events: EventReader<AssetEvent<MovementPreset>>,
+    presets: Res<Assets<MovementPreset>>,
+    mut query: Query<(
+        Ref<MovementPresetHandle>,
+        &mut LogicalPlayerProperties,
+        &LogicalPlayerController,
+        &PlayerHull,
+        &mut Transform,
+        &mut Collider,
+        &mut ShapeCaster,
+    ), With<LogicalPlayer>>
+) {
+    let updated_presets: Vec<AssetId<MovementPreset>> = asset_events
+        .read()
+        .filter_map(|asset_event| match *asset_event {
+            AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id } => Some(id),
+            _ => None,
+        })
+        .collect();
+
+    for (preset_handle, mut player_props, logical_controller, hull, mut transform, mut collider, mut shape_caster) in query.iter_mut() {
+        // a switched handle may point to a preset that finished loading earlier
+        if !preset_handle.is_changed() && !updated_presets.contains(&preset_handle.0.id()) {
+            continue;
+        }
+
+        let Some(preset) = presets.get(&preset_handle.0) else {
+            continue;
+        };
+
+        if let Err(error) = hull.validate(&preset.properties) {
+            warn!("not applying movement preset {:?}: {error}", preset_handle.0.path());
+            continue;
+        }
+
+        let old_height = player_props.hull_height(logical_controller.crouched);
+        let new_height = preset.properties.hull_height(logical_controller.crouched);
+
+        *player_props = preset.properties.clone();
+
+        if new_height != old_height {
+            hull.resize(&mut collider, &mut shape_caster, new_height);
+            // keep the feet where they were
+            transform.translation.y += (new_height - old_height) / 2.0;
+        }
+
+        info!("applied movement preset {:?}", preset_handle.0.path());
+    }
+}
+
+// Serializes angles stored in radians as degrees, so preset files stay readable
+pub(super) mod degrees {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(radians: &f32, serializer: S) -> Result<S::Ok, S::Error> {
+        radians.to_degrees().serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f32, D::Error> {
+        f32::deserialize(deserializer).map(f32::to_radians)
+    }
+}
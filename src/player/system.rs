@@ -1,27 +1,94 @@
-use avian3d::{math::*, parry::query::ShapeCastHit, prelude::*};
-use bevy::{ecs::query::QueryFilter, input::mouse::MouseMotion, prelude::*};
+use avian3d::{collision::contact_query, math::*, parry::query::ShapeCastHit, prelude::*};
+use bevy::{ecs::query::QueryFilter, input::mouse::{MouseMotion, MouseWheel}, prelude::*};
 
 use std::f32::consts::FRAC_PI_2;
 
 use crate::{constants::*, CursorLocked};
-use super::component::{LogicalPlayer, LogicalPlayerController, LogicalPlayerProperties, MoveMode, PlayerControls, PlayerInput, RenderPlayer};
+use crate::environment::volume::{GameLayer, Ladder, Water};
+use super::component::{GroundKind, GroundState, Health, LogicalPlayer, LogicalPlayerController, LogicalPlayerProperties, MoveMode, PlayerBody, PlayerControls, PlayerInput, PreviousTranslation, RenderPlayer, Stamina, WaterState};
+use super::controls::{read_wheel, BindingInput, InputBinding, Rebinding};
+use super::demo::DemoPlayback;
+use super::event::{LandedEvent, SlideEvent, SprintEvent};
+use super::hull::PlayerHull;
+use super::movement::{clip_velocity_planes, ladder_velocity, swim_velocity, wall_run_velocity, GroundHit, LadderHit, MovementInput, MovementState};
 
 // If the distance to the ground is less than this value, the player is considered grounded
 const GROUNDED_DISTANCE: f32 = 0.125;
 
 const SLIGHT_SCALE_DOWN: f32 = 0.9375;
 
+// Step casts start this far above the feet so the floor we stand on isn't reported as an obstacle
+const STEP_CAST_LIFT: f32 = 0.02;
+
+// Extra forward distance checked when looking for a step, so we detect ledges we are pressed against
+const STEP_CAST_SKIN: f32 = 0.05;
+
+const PITCH_LIMIT: f32 = FRAC_PI_2 - ANGLE_EPSILON;
+
+// Most surfaces the velocity is clipped against in one tick while airborne, Source uses 5 but only moves between them
+const MAX_CLIP_PLANES: usize = 4;
+
+// Extra distance the slide cast looks ahead, so surfaces we are already touching are found
+const SLIDE_CAST_SKIN: f32 = 0.05;
+
+// Most surfaces a kinematic player bumps into and slides along in one tick
+const MAX_BUMPS: usize = 4;
+
+// Gap a kinematic player keeps to whatever it sweeps into, so the next sweep doesn't start touching it
+const SKIN_WIDTH: f32 = 0.01;
+
+// Seconds after jumping off a ladder before it can be grabbed again, so the push off isn't undone right away
+const LADDER_REGRAB_DELAY: f32 = 0.3;
+
+// Immersion from which the player swims instead of wading, floating keeps it above this
+const SWIM_DEPTH: f32 = 0.4;
+
+// How far to the sides a wall is looked for while airborne
+const WALL_RUN_CAST_DISTANCE: f32 = 0.2;
+
+// Walls whose normal points further up or down than this can't be run along
+const WALL_RUN_MAX_NORMAL_Y: f32 = 0.2;
+
+// A wall facing this close to the last one run along counts as the same wall
+const WALL_REGRAB_DOT: f32 = 0.9;
+
+// How far in front of the hull a ledge is looked for while airborne
+const MANTLE_CAST_DISTANCE: f32 = 0.3;
+
+// A mantle that hasn't reached the ledge after this many seconds is blocked and given up
+const MANTLE_TIMEOUT: f32 = 1.0;
+
+// The mantle is done once the hull is this close to the ledge
+const MANTLE_DONE_DISTANCE: f32 = 0.02;
+
+// How quickly the camera eases towards its target height after the hull changes size
+const VIEW_OFFSET_EASE: f32 = 12.0;
+
+// How far the camera dips per unit of landing speed, and at most
+const LANDING_DIP_SCALE: f32 = 0.015;
+const LANDING_DIP_MAX: f32 = 0.3;
+
 // transforms raw input into PlayerInput
+// runs every frame and accumulates until the next fixed tick consumes it in consume_tick_input
 pub fn player_input(
     mut mouse_events: EventReader<MouseMotion>,
+    mut wheel_events: EventReader<MouseWheel>,
     keyboard_input: Res<ButtonInput<KeyCode>>,
+    mouse_button_input: Res<ButtonInput<MouseButton>>,
     mut query: Query<(&mut PlayerInput, &PlayerControls)>,
-    cursor_locked: Res<CursorLocked>
+    cursor_locked: Res<CursorLocked>,
+    playback: Option<Res<DemoPlayback>>,
+    rebinding: Option<Res<Rebinding>>
 ) {
     let Ok((mut player_input, player_controls)) = query.get_single_mut() else {
         return;
     };
 
+    // a playing demo provides the input, and a captured rebinding shouldn't also trigger its old action
+    if playback.is_some() || rebinding.is_some() {
+        return;
+    }
+
     if !cursor_locked.0 {
         *player_input = PlayerInput::default();
         return;
@@ -39,36 +106,48 @@ pub fn player_input(
         let delta_yaw = -delta.x * player_controls.mouse_sensitivity;
         let delta_pitch = -delta.y * player_controls.mouse_sensitivity;
 
-        player_input.yaw = delta_yaw;
-        player_input.pitch = delta_pitch;
-    } else {
-        player_input.yaw = 0.0;
-        player_input.pitch = 0.0;
+        player_input.yaw += delta_yaw;
+        player_input.pitch += delta_pitch;
     }
 
-    // keyboard
+    // keys, mouse buttons and wheel
+
+    let binding_input = BindingInput {
+        keys: &keyboard_input,
+        mouse_buttons: &mouse_button_input,
+        wheel: read_wheel(&mut wheel_events),
+    };
 
-    fn get_axis(key_input: &Res<ButtonInput<KeyCode>>, key_pos: KeyCode, key_neg: KeyCode) -> f32 {
-        get_pressed(key_input, key_pos) - get_pressed(key_input, key_neg)
+    fn get_axis(binding_input: &BindingInput, binding_pos: InputBinding, binding_neg: InputBinding) -> f32 {
+        get_pressed(binding_input, binding_pos) - get_pressed(binding_input, binding_neg)
     }
 
-    fn get_pressed(key_input: &Res<ButtonInput<KeyCode>>, key: KeyCode) -> f32 {
-        if key_input.pressed(key) {
+    fn get_pressed(binding_input: &BindingInput, binding: InputBinding) -> f32 {
+        if binding_input.pressed(binding) {
             1.0
         } else {
             0.0
         }
     }
 
+    // wheel bindings only fire for one frame, so they are latched like presses until a tick consumes them
+    fn get_held(binding_input: &BindingInput, binding: InputBinding, latched: bool) -> bool {
+        binding_input.pressed(binding) || (binding.is_wheel() && latched)
+    }
+
     player_input.movement = Vec3::new(
-        get_axis(&keyboard_input, player_controls.key_right, player_controls.key_left),
-        get_axis(&keyboard_input, player_controls.key_up, player_controls.key_down),
-        get_axis(&keyboard_input, player_controls.key_forward, player_controls.key_backward)
+        get_axis(&binding_input, player_controls.key_right, player_controls.key_left),
+        get_axis(&binding_input, player_controls.key_up, player_controls.key_down),
+        get_axis(&binding_input, player_controls.key_forward, player_controls.key_backward)
     );
 
-    // TODO: jump, crouch
-    player_input.fly = keyboard_input.just_pressed(player_controls.key_fly);
-    player_input.jump = keyboard_input.pressed(player_controls.key_jump);
+    // presses are latched so a frame without a fixed tick doesn't lose them
+    player_input.fly |= binding_input.just_pressed(player_controls.key_fly);
+    player_input.jump = get_held(&binding_input, player_controls.key_jump, player_input.jump);
+    player_input.crouch = get_held(&binding_input, player_controls.key_crouch, player_input.crouch);
+    player_input.sprint = get_held(&binding_input, player_controls.key_sprint, player_input.sprint);
+    player_input.fire = get_held(&binding_input, player_controls.key_fire, player_input.fire);
+    player_input.reload = get_held(&binding_input, player_controls.key_reload, player_input.reload);
 }
 
 // transforms PlayerInput into LogicPlayerData for look only
@@ -84,37 +163,125 @@ pub fn player_look(
 
     logical_controller.yaw += player_input.yaw;
 
-    const PITCH_LIMIT:f32 = FRAC_PI_2 - ANGLE_EPSILON;
-
     logical_controller.pitch = (logical_controller.pitch + player_input.pitch).clamp(-PITCH_LIMIT, PITCH_LIMIT);
 }
 
+// transforms PlayerInput into the sprint state of LogicPlayerController and drains/refills Stamina
+pub fn player_sprint(
+    time: Res<Time>,
+    mut sprint_events: EventWriter<SprintEvent>,
+    mut query: Query<(Entity, &PlayerInput, &mut LogicalPlayerController, &mut Stamina)>
+) {
+    let Ok((entity, player_input, mut logical_controller, mut stamina)) = query.get_single_mut() else {
+        return;
+    };
+
+    let delta_time = time.delta_secs();
+
+    // exhaustion lasts until the sprint key is let go
+    if !player_input.sprint {
+        stamina.exhausted = false;
+    }
+
+    let is_moving = player_input.movement.xz() != Vec2::ZERO;
+    let wants_sprint = player_input.sprint
+        && is_moving
+        && !logical_controller.crouched
+        && logical_controller.move_mode == MoveMode::Ground;
+    let can_sprint = wants_sprint && !stamina.exhausted && stamina.current > 0.0;
+
+    if can_sprint {
+        stamina.current = f32::max(stamina.current - stamina.drain_rate * delta_time, 0.0);
+        stamina.regen_timer = stamina.regen_delay;
+    } else if stamina.regen_timer > 0.0 {
+        stamina.regen_timer -= delta_time;
+    } else {
+        stamina.current = f32::min(stamina.current + stamina.regen_rate * delta_time, stamina.max);
+    }
+
+    let sprinting = can_sprint && stamina.current > 0.0;
+
+    if sprinting != logical_controller.sprinting {
+        if sprinting {
+            sprint_events.send(SprintEvent::Started(entity));
+        } else if can_sprint {
+            // still trying to sprint, so the only reason to stop is running out of stamina
+            stamina.exhausted = true;
+            sprint_events.send(SprintEvent::Exhausted(entity));
+        } else {
+            sprint_events.send(SprintEvent::Stopped(entity));
+        }
+        logical_controller.sprinting = sprinting;
+    }
+}
+
+// turns landings into fall damage, demos don't record health so playing one back doesn't hurt
+pub fn player_fall_damage(
+    playback: Option<Res<DemoPlayback>>,
+    mut landed_events: EventReader<LandedEvent>,
+    mut query: Query<(&LogicalPlayerProperties, &mut Health)>
+) {
+    if playback.is_some() {
+        landed_events.clear();
+        return;
+    }
+
+    for landed in landed_events.read() {
+        let Ok((player_props, mut health)) = query.get_mut(landed.entity) else {
+            continue;
+        };
+        health.current = f32::max(health.current - player_props.fall_damage(landed.speed), 0.0);
+    }
+}
+
 // transforms PlayerInput + a little LogicPlayerController (look) into LogicPlayerController (move)
 pub fn player_move(
+    mut commands: Commands,
     time: Res<Time>,
+    mut slide_events: EventWriter<SlideEvent>,
+    mut landed_events: EventWriter<LandedEvent>,
     spatial_query: SpatialQuery,
+    obstacles: Query<(&Collider, &Position, &Rotation), Without<LogicalPlayer>>,
+    collider_parents: Query<&ColliderParent>,
+    grounds: Query<(&Position, &LinearVelocity, &AngularVelocity), Without<LogicalPlayer>>,
+    ladders: Query<&GlobalTransform, With<Ladder>>,
+    waters: Query<&ColliderAabb, With<Water>>,
     mut query: Query<(
         Entity, 
-        &Transform,
+        &mut Transform,
         &Collider,
+        &mut ShapeCaster,
         &PlayerInput, 
         &LogicalPlayerProperties, 
+        &PlayerBody,
+        &PlayerHull,
         &mut LogicalPlayerController, 
         &mut LinearVelocity, 
-    )>
+        &mut GroundState,
+        &mut WaterState,
+    ), With<LogicalPlayer>>
 ) {
     let Ok((
         entity,
-        transform,
+        mut transform,
         collider,
+        mut shape_caster,
         player_input, 
         player_props, 
+        player_body,
+        hull,
         mut logical_controller, 
-        mut linear_velocity,)) = 
+        mut linear_velocity,
+        mut ground_state,
+        mut water_state,)) = 
     query.get_single_mut() else {
         return;
     };
 
+    // SpatialQuery reads every Collider, so a resized hull is inserted through commands at the end
+    let mut collider = collider.clone();
+    let mut hull_resized = false;
+
     let delta_time = time.delta_secs();
 
     if player_input.fly {
@@ -123,178 +290,889 @@ pub fn player_move(
                 // gravity_scale.0 = 1.0;
                 MoveMode::Ground
             },
-            MoveMode::Ground => {
+            MoveMode::Ground | MoveMode::Ladder | MoveMode::Swim | MoveMode::WallRun | MoveMode::Mantle => {
                 // gravity_scale.0 = 0.0;
                 MoveMode::Noclip
             }
         }
     }
 
-    if logical_controller.move_mode == MoveMode::Noclip {
-        let mut move_to_world = Mat3::from_euler(EulerRot::YXZ, logical_controller.yaw, logical_controller.pitch, 0.0);
-        move_to_world.z_axis *= -1.0; // Forward is -Z
-        move_to_world.y_axis = Vec3::Y; // Up is Y
-        linear_velocity.0 = move_to_world * player_input.movement * player_props.fly_velocity;
+    // a press is the first tick jump is held, it stays buffered for a moment so a jump pressed just before landing isn't lost
+    let jump_pressed = player_input.jump && !logical_controller.jump_held;
+    logical_controller.jump_held = player_input.jump;
+    logical_controller.jump_buffer = if jump_pressed {
+        player_props.jump_buffer_time
+    } else {
+        f32::max(logical_controller.jump_buffer - delta_time, 0.0)
+    };
 
-    } else if logical_controller.move_mode == MoveMode::Ground {
-        // shape cast towards ground
-        let filter = SpatialQueryFilter::default().with_excluded_entities([entity]);
-        let config = ShapeCastConfig::from_max_distance(GROUNDED_DISTANCE);
-        let ground_cast = spatial_query.cast_shape(
-            &scaled_collider_laterally(&collider, SLIGHT_SCALE_DOWN),
-            transform.translation,
-            transform.rotation,
-            -Dir3::Y,
-            &config,
-            &filter
-        );
+    // volumes are sensors on their own layer, movement only collides with solid colliders
+    let filter = SpatialQueryFilter::from_mask(GameLayer::Default).with_excluded_entities([entity]);
 
-        // Source engine movement
+    // water and ladders move along the view instead of the ground
+    let view_input = MovementInput {
+        movement: player_input.movement,
+        yaw: logical_controller.yaw,
+        pitch: logical_controller.pitch,
+        jump: player_input.jump,
+        ..default()
+    };
+    // only a new press pushes off a ladder, a jump held into it would drop it again right away
+    let ladder_input = MovementInput { jump: jump_pressed, ..view_input };
+    logical_controller.ladder_cooldown = f32::max(logical_controller.ladder_cooldown - delta_time, 0.0);
+    logical_controller.slide_cooldown = f32::max(logical_controller.slide_cooldown - delta_time, 0.0);
+    let ladder_hit = match logical_controller.move_mode {
+        MoveMode::Noclip => None,
+        MoveMode::Ground | MoveMode::Ladder | MoveMode::Swim | MoveMode::WallRun | MoveMode::Mantle => find_ladder(&spatial_query, &ladders, &collider, &transform),
+    };
 
-        let speeds = Vec3::new(player_props.side_speed, 0.0, player_props.forward_speed);
-        let mut move_to_world = Mat3::from_axis_angle(Vec3::Y, logical_controller.yaw);
-        move_to_world.z_axis *= -1.0; // Forward is -Z
-        let mut wish_direction = move_to_world * (player_input.movement * speeds); 
-        let mut wish_speed = wish_direction.length();
+    // in the air a touched ladder is grabbed, on the ground only when climbing up it so it can be walked away from
+    if logical_controller.move_mode == MoveMode::Ground && logical_controller.ladder_cooldown <= 0.0 {
+        if let Some(ladder_hit) = ladder_hit {
+            let climbs_up = Vec3::dot(ladder_velocity(&ladder_input, player_props, &ladder_hit), ladder_hit.axis) > 0.0;
+            if !ground_state.is_grounded() || climbs_up {
+                logical_controller.move_mode = MoveMode::Ladder;
+            }
+        }
+    }
 
-        if wish_speed > f32::EPSILON {
-            // avoid division by zero
-            wish_direction /= wish_speed; // effectively normalizes to unit circle, avoiding length computation twice
+    let hull_height = player_props.hull_height(logical_controller.crouched);
+    *water_state = match find_waterline(&spatial_query, &waters, &collider, &transform) {
+        Some(waterline) => {
+            let feet = transform.translation.y - hull.foot_offset(hull_height);
+            let eyes = transform.translation.y + hull.eye_offset(hull_height);
+            WaterState {
+                immersion: ((waterline - feet) / hull_height).clamp(0.0, 1.0),
+                underwater: eyes < waterline,
+            }
+        },
+        None => WaterState::default(),
+    };
+
+    // wading in deeper than the waist starts swimming, unless jumping out of the water
+    if logical_controller.move_mode == MoveMode::Ground && water_state.immersion >= SWIM_DEPTH && linear_velocity.y <= 0.0 {
+        logical_controller.move_mode = MoveMode::Swim;
+    }
+
+    // wall running: airborne, fast and pressing forward next to a near vertical wall that isn't the last one run along
+    let horizontal_velocity = Vec3::new(linear_velocity.x, 0.0, linear_velocity.z);
+    if logical_controller.move_mode == MoveMode::Ground
+        && !ground_state.is_grounded()
+        && !logical_controller.crouched
+        && player_input.movement.z > 0.0
+        && horizontal_velocity.length() >= player_props.wall_run_min_speed
+    {
+        let right = Vec3::cross(horizontal_velocity.normalize(), Vec3::Y);
+        if let Some(wall_normal) = find_wall(&spatial_query, &collider, &transform, &[right, -right], &filter) {
+            if Vec3::dot(wall_normal, logical_controller.wall_normal) < WALL_REGRAB_DOT {
+                logical_controller.move_mode = MoveMode::WallRun;
+                logical_controller.wall_normal = wall_normal;
+                logical_controller.wall_run_time = 0.0;
+                // the run starts level instead of keeping the fall speed
+                linear_velocity.y = f32::max(linear_velocity.y, 0.0);
+            }
         }
+    }
 
-        // TODO: crouch and sprint speed
-        let max_speed = player_props.walk_speed;
+    // mantling: airborne and pressing forward into a ledge that is too high to step onto
+    if logical_controller.move_mode == MoveMode::Ground && !ground_state.is_grounded() && player_input.movement.z > 0.0 {
+        let forward = Dir3::new_unchecked(Quat::from_rotation_y(logical_controller.yaw) * Vec3::NEG_Z);
+        if let Some(mantle_target) = find_mantle_target(&spatial_query, &collider, &transform, forward, hull, hull_height, player_props, &filter) {
+            logical_controller.move_mode = MoveMode::Mantle;
+            logical_controller.mantle_target = mantle_target;
+            logical_controller.mantle_time = 0.0;
+        }
+    }
 
-        wish_speed = f32::min(wish_speed, max_speed); 
+    // every move mode but noclip collides with the level: the solver pushes a dynamic body out of it,
+    // a kinematic one has to do it itself
+    let collides = logical_controller.move_mode != MoveMode::Noclip;
+    if collides && *player_body == PlayerBody::Kinematic {
+        transform.translation = depenetrate(&spatial_query, &obstacles, &collider, &transform, &filter);
+    }
 
-        if let Some(shape_hit_data) = ground_cast {
-            // on the ground
+    match logical_controller.move_mode {
+        MoveMode::Noclip => {
+            let mut move_to_world = Mat3::from_euler(EulerRot::YXZ, logical_controller.yaw, logical_controller.pitch, 0.0);
+            move_to_world.z_axis *= -1.0; // Forward is -Z
+            move_to_world.y_axis = Vec3::Y; // Up is Y
+            linear_velocity.0 = move_to_world * player_input.movement * player_props.fly_velocity;
+            logical_controller.base_velocity = Vec3::ZERO;
+            logical_controller.airborne = false;
+            *ground_state = GroundState::default();
+        },
+        MoveMode::Ladder => {
+            leave_ground(&mut logical_controller, &mut ground_state);
+            if let Some(ladder_hit) = ladder_hit {
+                linear_velocity.0 = ladder_velocity(&ladder_input, player_props, &ladder_hit);
 
-            let has_traction = Vec3::dot(shape_hit_data.normal1, Vec3::Y) > player_props.traction_normal_cutoff;
+                let on_ground = probe_walkable_ground(&spatial_query, hull, &transform, player_props, logical_controller.crouched, &filter);
 
-            // only apply friction after at least one tick, allows b-hopping without losing speed
-            if logical_controller.ground_tick >= 1 && has_traction {
-                let lateral_speed = linear_velocity.xz().length();
-                if lateral_speed > player_props.friction_speed_cutoff {
-                    let control = f32::max(lateral_speed, player_props.stop_speed);
-                    let drop = control * player_props.friction * delta_time;
-                    let new_speed = f32::max((lateral_speed - drop) / lateral_speed, 0.0);
-                    linear_velocity.x *= new_speed;
-                    linear_velocity.z *= new_speed;
-                } else {
-                    linear_velocity.0 = Vec3::ZERO;
+                if jump_pressed {
+                    logical_controller.move_mode = MoveMode::Ground;
+                    logical_controller.ladder_cooldown = LADDER_REGRAB_DELAY;
+                    logical_controller.jump_buffer = 0.0;
+                } else if on_ground && Vec3::dot(linear_velocity.0, ladder_hit.axis) < 0.0 {
+                    // climbed down to the floor
+                    logical_controller.move_mode = MoveMode::Ground;
+                }
+            } else {
+                // climbed past either end, the velocity carries the player onto the ledge or off the bottom
+                logical_controller.move_mode = MoveMode::Ground;
+            }
+        },
+        MoveMode::Swim => {
+            leave_ground(&mut logical_controller, &mut ground_state);
+            if player_input.jump && !water_state.underwater {
+                // jump out at the waterline, gravity takes over until the player lands or falls back in
+                linear_velocity.0.y = player_props.jump_impulse;
+                logical_controller.move_mode = MoveMode::Ground;
+                logical_controller.jump_buffer = 0.0;
+            } else {
+                linear_velocity.0 = swim_velocity(linear_velocity.0, &view_input, player_props, water_state.immersion, delta_time);
+                if water_state.immersion < SWIM_DEPTH {
+                    logical_controller.move_mode = MoveMode::Ground;
                 }
             }
+        },
+        MoveMode::WallRun => {
+            leave_ground(&mut logical_controller, &mut ground_state);
+            let on_ground = probe_walkable_ground(&spatial_query, hull, &transform, player_props, logical_controller.crouched, &filter);
 
-            let mut add = accelerate(
-                wish_direction,
-                wish_speed,
-                player_props.acceleration,
-                linear_velocity.0,
-                delta_time,
+            logical_controller.wall_run_time += delta_time;
+            let wall_normal = find_wall(&spatial_query, &collider, &transform, &[-logical_controller.wall_normal], &filter);
+
+            match wall_normal {
+                Some(wall_normal) if !on_ground && player_input.movement.z > 0.0 && logical_controller.wall_run_time < player_props.wall_run_max_time => {
+                    // the wall may curve
+                    logical_controller.wall_normal = wall_normal;
+                    linear_velocity.0 = wall_run_velocity(linear_velocity.0, wall_normal, player_input.jump, player_props, delta_time);
+                    if player_input.jump {
+                        logical_controller.move_mode = MoveMode::Ground;
+                        logical_controller.jump_buffer = 0.0;
+                    }
+                },
+                _ => {
+                    // landed, ran out of wall or time, or let go
+                    logical_controller.move_mode = MoveMode::Ground;
+                },
+            }
+        },
+        MoveMode::Mantle => {
+            leave_ground(&mut logical_controller, &mut ground_state);
+            logical_controller.mantle_time += delta_time;
+            let to_target = logical_controller.mantle_target - transform.translation;
+
+            if to_target.length() < MANTLE_DONE_DISTANCE {
+                logical_controller.move_mode = MoveMode::Ground;
+                linear_velocity.0 = Vec3::ZERO;
+            } else if player_input.movement.z <= 0.0 || logical_controller.mantle_time > MANTLE_TIMEOUT {
+                // let go or blocked, fall back down
+                logical_controller.move_mode = MoveMode::Ground;
+                linear_velocity.0 = Vec3::ZERO;
+            } else if to_target.y > MANTLE_DONE_DISTANCE {
+                // rise along the wall first, then move over the ledge
+                linear_velocity.0 = Vec3::Y * f32::min(player_props.mantle_speed, to_target.y / delta_time);
+            } else {
+                let over_ledge = Vec3::new(to_target.x, 0.0, to_target.z);
+                linear_velocity.0 = over_ledge.clamp_length_max(player_props.mantle_speed * delta_time) / delta_time;
+            }
+        },
+        MoveMode::Ground => {
+            // shape cast towards ground
+            let config = ShapeCastConfig::from_max_distance(GROUNDED_DISTANCE);
+            let probe = hull.scaled_laterally(player_props.hull_height(logical_controller.crouched), SLIGHT_SCALE_DOWN);
+            let mut ground_cast = spatial_query.cast_shape(
+                &probe,
+                transform.translation,
+                transform.rotation,
+                -Dir3::Y,
+                &config,
+                &filter
             );
-            if !has_traction { // basically turns off gravity if surfing right?
-                // info!("no traction");
-                add.y -= player_props.gravity * delta_time;
+
+            // step down: stay glued to stairs and slopes instead of going briefly airborne
+            if ground_cast.is_none() && logical_controller.ground_tick >= 1 && linear_velocity.y <= 0.0 {
+                if let Some(step_hit) = find_step_down(&spatial_query, &probe, &transform, player_props, &filter) {
+                    transform.translation.y -= step_hit.distance;
+                    ground_cast = Some(step_hit);
+                }
             }
-            linear_velocity.0 += add;
 
-            if has_traction {
-                let linear_velocity_2 = linear_velocity.0;
-                // (how much current velocity aligns with hit_normal) * in the direction of hit_normal.
-                linear_velocity.0 -= Vec3::dot(linear_velocity_2, shape_hit_data.normal1) * shape_hit_data.normal1;
+            // crouching: on the ground the feet stay put, in the air the legs are pulled up
+            let on_ground = ground_cast.is_some();
+            if player_input.crouch && !logical_controller.crouched {
+                let height_change = player_props.stand_height - player_props.crouch_height;
+                hull.resize(&mut collider, &mut shape_caster, player_props.crouch_height);
+                hull_resized = true;
+                transform.translation.y += if on_ground { -height_change / 2.0 } else { height_change / 2.0 };
+                logical_controller.crouched = true;
 
-                if player_input.jump {
-                    linear_velocity.y += player_props.jump_impulse;
-                    // info!("jump")
+                // crouching while sprinting on the ground slides
+                let on_walkable_ground = ground_cast.as_ref().is_some_and(|hit| player_props.is_walkable(hit.normal1));
+                let speed = Vec3::new(linear_velocity.x, 0.0, linear_velocity.z).length();
+                if on_walkable_ground
+                    && logical_controller.sprinting
+                    && logical_controller.slide_cooldown <= 0.0
+                    && speed >= player_props.slide_min_speed
+                {
+                    logical_controller.sliding = true;
+                    slide_events.send(SlideEvent::Started(entity));
+                }
+            } else if !player_input.crouch && logical_controller.crouched {
+                if let Some(offset) = find_uncrouch_offset(&spatial_query, &probe, &transform, player_props, on_ground, &filter) {
+                    hull.resize(&mut collider, &mut shape_caster, player_props.stand_height);
+                    hull_resized = true;
+                    transform.translation.y += offset;
+                    logical_controller.crouched = false;
                 }
             }
 
-            // Increment ground tick but cap at max value
-            logical_controller.ground_tick = logical_controller.ground_tick.saturating_add(1);
-        } else {
-            // airborne
-            // info!("airborne");
+            // Source engine movement
 
-            logical_controller.ground_tick = 0;
-            wish_speed = f32::min(wish_speed, player_props.air_speed_cap);
+            *ground_state = match &ground_cast {
+                Some(shape_hit_data) => GroundState::from_hit(shape_hit_data, player_props),
+                None => GroundState::default(),
+            };
 
-            let mut add = accelerate(
-                wish_direction,
-                wish_speed,
-                player_props.air_acceleration,
-                linear_velocity.0,
+            // moving platforms: walking happens relative to the ground, which carries the player along and turns its view
+            let feet = transform.translation - Vec3::Y * hull.foot_offset(player_props.hull_height(logical_controller.crouched));
+            if let Some((velocity, yaw_rate)) = ground_state.entity.and_then(|ground| ground_motion(&collider_parents, &grounds, ground, feet, delta_time)) {
+                ground_state.velocity = velocity;
+                if ground_state.kind == GroundKind::Walkable {
+                    logical_controller.yaw += yaw_rate * delta_time;
+                }
+            }
+
+            // landing makes every wall runnable again
+            if ground_state.kind == GroundKind::Walkable {
+                logical_controller.wall_normal = Vec3::ZERO;
+            }
+
+            // in the air the platform's horizontal momentum is kept, so air control and the air speed cap act relative to it
+            let base_velocity = if ground_state.kind == GroundKind::Walkable {
+                logical_controller.base_velocity = Vec3::new(ground_state.velocity.x, 0.0, ground_state.velocity.z);
+                ground_state.velocity
+            } else {
+                logical_controller.base_velocity
+            };
+
+            // holding jump keeps jumping with auto bhop, otherwise every jump needs its own (buffered) press
+            let wants_jump = jump_pressed || logical_controller.jump_buffer > 0.0 || (player_props.auto_bhop && player_input.jump);
+
+            // coyote time: jumping still works for a moment after walking off a ledge
+            logical_controller.coyote_timer = if ground_state.kind == GroundKind::Walkable {
+                player_props.coyote_time
+            } else {
+                f32::max(logical_controller.coyote_timer - delta_time, 0.0)
+            };
+            let coyote_jump = wants_jump && ground_state.kind == GroundKind::Airborne && logical_controller.coyote_timer > 0.0;
+            // extra jumps need a fresh press in the air, so a held or buffered jump isn't spent on them before landing
+            let air_jump = jump_pressed
+                && !coyote_jump
+                && ground_state.kind == GroundKind::Airborne
+                && logical_controller.extra_jumps > 0;
+            let jumped = (wants_jump && ground_state.kind == GroundKind::Walkable) || coyote_jump || air_jump;
+
+            let movement_input = MovementInput {
+                movement: player_input.movement,
+                yaw: logical_controller.yaw,
+                pitch: logical_controller.pitch,
+                jump: wants_jump,
+                crouched: logical_controller.crouched,
+                sprinting: logical_controller.sprinting,
+                sliding: logical_controller.sliding,
+            };
+            let movement_state = MovementState {
+                velocity: linear_velocity.0 - base_velocity,
+                ground_tick: logical_controller.ground_tick,
+            }.step(
+                &movement_input,
+                player_props,
+                ground_cast.map(|shape_hit_data| GroundHit { normal: shape_hit_data.normal1 }),
                 delta_time,
             );
-            add.y = -player_props.gravity * delta_time;
-            linear_velocity.0 += add;
-
-            let air_speed = linear_velocity.xz().length();
-            if air_speed > player_props.max_air_speed {
-                let ratio = player_props.max_air_speed / air_speed;
-                linear_velocity.x *= ratio;
-                linear_velocity.z *= ratio;
+
+            linear_velocity.0 = movement_state.velocity + base_velocity;
+
+            // leaving a ladder, the water or a mantle onto the ground isn't a landing
+            if logical_controller.airborne && movement_state.ground_tick >= 1 {
+                landed_events.send(LandedEvent {
+                    entity,
+                    speed: f32::max(logical_controller.fall_speed + ground_state.velocity.y, 0.0),
+                    normal: ground_state.normal,
+                    ground_entity: ground_state.entity,
+                });
+            }
+            logical_controller.ground_tick = movement_state.ground_tick;
+            logical_controller.airborne = logical_controller.ground_tick == 0;
+
+            if coyote_jump || air_jump {
+                linear_velocity.0.y = player_props.jump_impulse;
+            }
+            if air_jump {
+                logical_controller.extra_jumps -= 1;
+            }
+            if logical_controller.ground_tick >= 1 {
+                logical_controller.extra_jumps = player_props.extra_jumps;
             }
+
+            // variable jump height: holding jump weakens gravity for a moment, letting go early cuts the jump short
+            if jumped {
+                logical_controller.jump_buffer = 0.0;
+                logical_controller.coyote_timer = 0.0;
+                logical_controller.jump_hold_timer = player_props.jump_hold_time;
+            } else if ground_state.kind == GroundKind::Walkable || linear_velocity.y <= 0.0 {
+                logical_controller.jump_hold_timer = 0.0;
+            } else if logical_controller.jump_hold_timer > 0.0 {
+                if player_input.jump {
+                    linear_velocity.0.y += player_props.gravity * (1.0 - player_props.jump_hold_gravity_scale) * delta_time;
+                    logical_controller.jump_hold_timer = f32::max(logical_controller.jump_hold_timer - delta_time, 0.0);
+                } else {
+                    linear_velocity.0.y *= player_props.jump_release_factor;
+                    logical_controller.jump_hold_timer = 0.0;
+                }
+            }
+            logical_controller.fall_speed = f32::max(-linear_velocity.y, 0.0);
+
+            // collide and slide: clip against ramps and walls here instead of leaving them to the solver, which leaks speed
+            if ground_state.kind != GroundKind::Walkable && *player_body == PlayerBody::Dynamic {
+                linear_velocity.0 = slide_velocity(&spatial_query, &collider, &transform, linear_velocity.0, player_props, &filter, delta_time);
+            }
+
+            if ground_state.kind == GroundKind::Walkable && !jumped {
+                if let Some(step_height) = find_step_up(&spatial_query, &collider, &transform, linear_velocity.0, player_props, &filter, delta_time) {
+                    transform.translation.y += step_height;
+                }
+            }
+        },
+    }
+
+    // a kinematic body moves exactly by its velocity, so it becomes the distance the sweep allows this tick
+    if collides && *player_body == PlayerBody::Kinematic {
+        let target = sweep_and_slide(&spatial_query, &collider, &transform, linear_velocity.0, player_props, &filter, delta_time);
+        linear_velocity.0 = (target - transform.translation) / delta_time;
+    }
+
+    if hull_resized {
+        commands.entity(entity).insert(collider);
+    }
+
+    // slides end when standing up, slowing down on the ground or switching to another move mode
+    if logical_controller.sliding {
+        let speed = Vec3::new(linear_velocity.x, 0.0, linear_velocity.z).length();
+        let too_slow = ground_state.kind == GroundKind::Walkable && speed < player_props.slide_min_speed;
+        if logical_controller.move_mode != MoveMode::Ground || !logical_controller.crouched || too_slow {
+            logical_controller.sliding = false;
+            logical_controller.slide_cooldown = player_props.slide_cooldown;
+            slide_events.send(SlideEvent::Stopped(entity));
         }
     }
 }
 
+// resets the accumulated deltas and latched presses once a fixed tick has used them
+pub fn consume_tick_input(
+    mut query: Query<(&mut PlayerInput, &PlayerControls)>
+) {
+    let Ok((mut player_input, player_controls)) = query.get_single_mut() else {
+        return;
+    };
+
+    player_input.yaw = 0.0;
+    player_input.pitch = 0.0;
+    player_input.fly = false;
+
+    if player_controls.key_jump.is_wheel() {
+        player_input.jump = false;
+    }
+    if player_controls.key_crouch.is_wheel() {
+        player_input.crouch = false;
+    }
+    if player_controls.key_sprint.is_wheel() {
+        player_input.sprint = false;
+    }
+    if player_controls.key_fire.is_wheel() {
+        player_input.fire = false;
+    }
+    if player_controls.key_reload.is_wheel() {
+        player_input.reload = false;
+    }
+}
+
+// swaps the RigidBody of players whose PlayerBody changed
+pub fn apply_player_body(
+    mut query: Query<(&PlayerBody, &mut RigidBody), Changed<PlayerBody>>
+) {
+    for (player_body, mut rigid_body) in query.iter_mut() {
+        *rigid_body = match player_body {
+            PlayerBody::Dynamic => RigidBody::Dynamic,
+            PlayerBody::Kinematic => RigidBody::Kinematic,
+        };
+    }
+}
+
+// remembers where the logical player was before this tick, so player_render can interpolate between ticks
+pub fn store_previous_translation(
+    mut query: Query<(&Transform, &mut PreviousTranslation), With<LogicalPlayer>>
+) {
+    let Ok((transform, mut previous_translation)) = query.get_single_mut() else {
+        return;
+    };
+
+    previous_translation.0 = transform.translation;
+}
+
 // render the LogicPlayerData by transfering logic to render_player
 pub fn player_render(
-    mut render_query: Query<(&mut Transform, &RenderPlayer), With<RenderPlayer>>,
-    logical_query: Query<(&Transform, &LogicalPlayerController, &Collider), (With<LogicalPlayer>, Without<RenderPlayer>)>
+    time: Res<Time>,
+    fixed_time: Res<Time<Fixed>>,
+    mut landed_events: EventReader<LandedEvent>,
+    mut render_query: Query<(&mut Transform, &mut RenderPlayer), With<RenderPlayer>>,
+    logical_query: Query<(&Transform, &PreviousTranslation, &LogicalPlayerController, &PlayerInput, &LogicalPlayerProperties, &PlayerHull), (With<LogicalPlayer>, Without<RenderPlayer>)>
 ) {
 
-    let Ok((mut render_transform, render_player)) = render_query.get_single_mut() else {
+    let Ok((mut render_transform, mut render_player)) = render_query.get_single_mut() else {
         return;
     };
 
-    let Ok((logical_transform, logical_controller, collider)) = logical_query.get(render_player.logical_entity) else {
+    let Ok((logical_transform, previous_translation, logical_controller, player_input, player_props, hull)) = logical_query.get(render_player.logical_entity) else {
         return;
     };
 
-    let camera_offset = Vec3::Y * -0.5;
-    let collider_offset = collider_y_offset(collider);
+    let hull_height = player_props.hull_height(logical_controller.crouched);
+    let logical_translation = previous_translation.0.lerp(logical_transform.translation, fixed_time.overstep_fraction());
+    let target_translation = logical_translation + Vec3::Y * hull.eye_offset(hull_height);
 
-    render_transform.translation = logical_transform.translation + collider_offset + camera_offset;
-    render_transform.rotation = Quat::from_euler(EulerRot::YXZ, logical_controller.yaw, logical_controller.pitch, 0.0);
+    // when the hull is resized (crouch), keep the camera where it was and ease it to the new eye height
+    if hull_height != render_player.hull_height {
+        render_player.view_offset = render_transform.translation.y - target_translation.y;
+        render_player.hull_height = hull_height;
+    }
+    // mantling lifts the hull quickly, the camera trails behind it the same way
+    if logical_controller.move_mode == MoveMode::Mantle {
+        render_player.view_offset = render_transform.translation.y - target_translation.y;
+    }
+    // landings dip the camera, harder the faster the fall
+    for landed in landed_events.read().filter(|landed| landed.entity == render_player.logical_entity) {
+        render_player.view_offset -= f32::min(landed.speed * LANDING_DIP_SCALE, LANDING_DIP_MAX);
+    }
+    render_player.view_offset *= f32::exp(-VIEW_OFFSET_EASE * time.delta_secs());
 
+    render_transform.translation = target_translation + Vec3::Y * render_player.view_offset;
+    // look input not yet consumed by a tick is added so the view stays responsive between ticks
+    let yaw = logical_controller.yaw + player_input.yaw;
+    let pitch = (logical_controller.pitch + player_input.pitch).clamp(-PITCH_LIMIT, PITCH_LIMIT);
+    render_transform.rotation = Quat::from_euler(EulerRot::YXZ, yaw, pitch, 0.0);
+
+}
+
+/// Resets the ground tracking for the move modes that don't stand on the ground.
+/// The jumps they end with use up the jump buffer themselves, coyote time only follows walking off a ledge.
+fn leave_ground(logical_controller: &mut LogicalPlayerController, ground_state: &mut GroundState) {
+    *ground_state = GroundState::default();
+    logical_controller.ground_tick = 0;
+    logical_controller.base_velocity = Vec3::ZERO;
+    logical_controller.jump_hold_timer = 0.0;
+    logical_controller.fall_speed = 0.0;
+    logical_controller.airborne = false;
+    logical_controller.coyote_timer = 0.0;
 }
 
-/// Returns the offset that puts a point at the center of the player transform to the bottom of the collider.
-/// Needed for when we want to originate something at the foot of the player.
-fn collider_y_offset(collider: &Collider) -> Vec3 {
-    Vec3::Y * if let Some(cylinder) = collider.shape().as_cylinder() {
-        cylinder.half_height
+/// Whether walkable ground is right below the hull, for the move modes that don't track the ground but end when reaching it.
+fn probe_walkable_ground(
+    spatial_query: &SpatialQuery,
+    hull: &PlayerHull,
+    transform: &Transform,
+    player_props: &LogicalPlayerProperties,
+    crouched: bool,
+    filter: &SpatialQueryFilter,
+) -> bool {
+    let probe = hull.scaled_laterally(player_props.hull_height(crouched), SLIGHT_SCALE_DOWN);
+    spatial_query.cast_shape(
+        &probe,
+        transform.translation,
+        transform.rotation,
+        -Dir3::Y,
+        &ShapeCastConfig::from_max_distance(GROUNDED_DISTANCE),
+        filter
+    ).is_some_and(|ground_hit| player_props.is_walkable(ground_hit.normal1))
+}
+
+/// Checks whether a crouched player has room to stand up.
+/// Returns the vertical offset to apply to the player's translation if standing is possible.
+/// In the air the legs are extended downwards first, otherwise the head is raised from the feet.
+/// `probe` is the crouched hull scaled down laterally.
+fn find_uncrouch_offset(
+    spatial_query: &SpatialQuery,
+    probe: &Collider,
+    transform: &Transform,
+    player_props: &LogicalPlayerProperties,
+    on_ground: bool,
+    filter: &SpatialQueryFilter,
+) -> Option<f32> {
+    let height_change = player_props.stand_height - player_props.crouch_height;
+    let config = ShapeCastConfig {
+        ignore_origin_penetration: true,
+        ..ShapeCastConfig::from_max_distance(height_change)
+    };
+
+    let has_room = |direction: Dir3| {
+        spatial_query.cast_shape(
+            probe,
+            transform.translation,
+            transform.rotation,
+            direction,
+            &config,
+            filter
+        ).is_none()
+    };
+
+    if !on_ground && has_room(Dir3::NEG_Y) {
+        Some(-height_change / 2.0)
+    } else if has_room(Dir3::Y) {
+        Some(height_change / 2.0)
     } else {
-        panic!("Controller must use a cylinder collider")
+        None
     }
 }
 
-/// Return a collider that is scaled laterally (XZ plane) but not vertically (Y axis).
-fn scaled_collider_laterally(collider: &Collider, scale: f32) -> Collider {
-    if let Some(cylinder) = collider.shape().as_cylinder() {
-        let new_cylinder = Collider::cylinder(cylinder.radius * scale, cylinder.half_height * 2.0);
-        new_cylinder
-    } else {
-        panic!("Controller must use a cylinder  collider")
+/// Looks for a ledge lower than `max_step_height` in the direction of horizontal movement.
+/// Casts forward to find an obstacle, then up, forward again and down to find the top of the step.
+/// Returns how far the player has to be raised to stand on it.
+fn find_step_up(
+    spatial_query: &SpatialQuery,
+    collider: &Collider,
+    transform: &Transform,
+    velocity: Vec3,
+    player_props: &LogicalPlayerProperties,
+    filter: &SpatialQueryFilter,
+    delta_time: f32,
+) -> Option<f32> {
+    let horizontal_velocity = Vec3::new(velocity.x, 0.0, velocity.z);
+    let Ok(direction) = Dir3::new(horizontal_velocity) else {
+        return None;
+    };
+    let forward_distance = horizontal_velocity.length() * delta_time + STEP_CAST_SKIN;
+
+    // the hull is usually pressed against the obstacle, so initial contacts are ignored
+    let cast = |origin: Vec3, direction: Dir3, max_distance: f32| {
+        let config = ShapeCastConfig {
+            ignore_origin_penetration: true,
+            ..ShapeCastConfig::from_max_distance(max_distance)
+        };
+        spatial_query.cast_shape(collider, origin, transform.rotation, direction, &config, filter)
+    };
+
+    // is something in the way that we can't walk up normally?
+    let start = transform.translation + Vec3::Y * STEP_CAST_LIFT;
+    let obstacle = cast(start, direction, forward_distance)?;
+    if player_props.is_walkable(obstacle.normal1) {
+        return None;
+    }
+
+    // how far up can we go?
+    let max_up_distance = player_props.max_step_height - STEP_CAST_LIFT;
+    let up_distance = cast(start, Dir3::Y, max_up_distance).map_or(max_up_distance, |hit| hit.distance);
+    let raised = start + Vec3::Y * up_distance;
+
+    // is there room to move forward at that height?
+    if cast(raised, direction, forward_distance).is_some() {
+        return None;
     }
+
+    // find the top of the step
+    let ahead = raised + direction * forward_distance;
+    let step_hit = cast(ahead, Dir3::NEG_Y, up_distance + STEP_CAST_LIFT)?;
+    if !player_props.is_walkable(step_hit.normal1) {
+        return None;
+    }
+
+    let step_height = ahead.y - step_hit.distance - transform.translation.y;
+    (step_height > 0.0).then_some(step_height)
 }
 
+/// Looks for walkable ground at most `max_step_height` below the player.
+/// `probe` is the hull scaled down laterally, like for the ground cast.
+fn find_step_down(
+    spatial_query: &SpatialQuery,
+    probe: &Collider,
+    transform: &Transform,
+    player_props: &LogicalPlayerProperties,
+    filter: &SpatialQueryFilter,
+) -> Option<ShapeHitData> {
+    let config = ShapeCastConfig::from_max_distance(player_props.max_step_height);
+    let step_hit = spatial_query.cast_shape(
+        probe,
+        transform.translation,
+        transform.rotation,
+        Dir3::NEG_Y,
+        &config,
+        filter
+    )?;
+
+    player_props.is_walkable(step_hit.normal1).then_some(step_hit)
+}
+
+/// Clips the velocity against every surface the hull would run into during this tick.
+/// Walkable surfaces are left out, landing on them is up to the ground cast.
+fn slide_velocity(
+    spatial_query: &SpatialQuery,
+    collider: &Collider,
+    transform: &Transform,
+    velocity: Vec3,
+    player_props: &LogicalPlayerProperties,
+    filter: &SpatialQueryFilter,
+    delta_time: f32,
+) -> Vec3 {
+    let mut planes: Vec<Vec3> = Vec::with_capacity(MAX_CLIP_PLANES);
+    let mut clipped_velocity = velocity;
+
+    while planes.len() < MAX_CLIP_PLANES {
+        let Ok(direction) = Dir3::new(clipped_velocity) else {
+            break;
+        };
+
+        let config = ShapeCastConfig {
+            ignore_origin_penetration: true,
+            ..ShapeCastConfig::from_max_distance(clipped_velocity.length() * delta_time + SLIDE_CAST_SKIN)
+        };
+        let Some(hit) = spatial_query.cast_shape(collider, transform.translation, transform.rotation, direction, &config, filter) else {
+            break;
+        };
+
+        // hitting a plane we already slide along again means there is nothing new in the way
+        if player_props.is_walkable(hit.normal1) || planes.iter().any(|&plane| Vec3::dot(plane, hit.normal1) > 1.0 - ANGLE_EPSILON) {
+            break;
+        }
+
+        planes.push(hit.normal1);
+        clipped_velocity = clip_velocity_planes(velocity, &planes, player_props.overbounce);
+    }
 
-fn accelerate(
-    wish_direction: Vec3,
-    wish_speed: f32,
-    acceleration: f32,
+    clipped_velocity
+}
+
+/// Moves the hull along the velocity for one tick, sliding along everything it bumps into like Source's TryPlayerMove.
+/// Returns where the hull ends up, `SKIN_WIDTH` away from the surfaces it hit.
+fn sweep_and_slide(
+    spatial_query: &SpatialQuery,
+    collider: &Collider,
+    transform: &Transform,
     velocity: Vec3,
-    dt: f32,
+    player_props: &LogicalPlayerProperties,
+    filter: &SpatialQueryFilter,
+    delta_time: f32,
 ) -> Vec3 {
-    let velocity_projection = Vec3::dot(velocity, wish_direction);
-    let add_speed = wish_speed - velocity_projection;
-    if add_speed <= 0.0 {
-        return Vec3::ZERO;
+    let mut translation = transform.translation;
+    let mut planes: Vec<Vec3> = Vec::with_capacity(MAX_BUMPS);
+    let mut slide_velocity = velocity;
+    let mut time_left = delta_time;
+
+    for _ in 0..MAX_BUMPS {
+        let movement = slide_velocity * time_left;
+        let distance = movement.length();
+        let Ok(direction) = Dir3::new(movement) else {
+            break;
+        };
+
+        let config = ShapeCastConfig {
+            ignore_origin_penetration: true,
+            ..ShapeCastConfig::from_max_distance(distance + SKIN_WIDTH)
+        };
+        let Some(hit) = spatial_query.cast_shape(collider, translation, transform.rotation, direction, &config, filter) else {
+            translation += movement;
+            break;
+        };
+
+        let travel = (hit.distance - SKIN_WIDTH).clamp(0.0, distance);
+        translation += direction * travel;
+        time_left *= 1.0 - travel / distance;
+
+        planes.push(hit.normal1);
+        slide_velocity = clip_velocity_planes(velocity, &planes, player_props.overbounce);
+
+        // turned around in a corner, stop instead of jittering back and forth
+        if Vec3::dot(slide_velocity, velocity) <= 0.0 {
+            break;
+        }
     }
 
-    let acceleration_speed = f32::min(acceleration * wish_speed * dt, add_speed);
-    wish_direction * acceleration_speed
-}
\ No newline at end of file
+    translation
+}
+
+/// Finds a ladder volume the hull overlaps, with the axis to climb along and the side of it the player is on.
+fn find_ladder(
+    spatial_query: &SpatialQuery,
+    ladders: &Query<&GlobalTransform, With<Ladder>>,
+    collider: &Collider,
+    transform: &Transform,
+) -> Option<LadderHit> {
+    let filter = SpatialQueryFilter::from_mask(GameLayer::Volume);
+
+    spatial_query.shape_intersections(collider, transform.translation, transform.rotation, &filter)
+        .into_iter()
+        .find_map(|volume| {
+            let ladder_transform = ladders.get(volume).ok()?;
+
+            let mut axis = ladder_transform.up().as_vec3();
+            if axis.y < 0.0 {
+                axis = -axis;
+            }
+
+            let offset = transform.translation - ladder_transform.translation();
+            let outward = offset - Vec3::dot(offset, axis) * axis;
+            let outward = Vec3::new(outward.x, 0.0, outward.z).normalize_or_zero();
+
+            Some(LadderHit { axis, outward })
+        })
+}
+
+/// Casts the hull a short distance in each of the `directions` and returns the normal of the first near vertical wall found.
+fn find_wall(
+    spatial_query: &SpatialQuery,
+    collider: &Collider,
+    transform: &Transform,
+    directions: &[Vec3],
+    filter: &SpatialQueryFilter,
+) -> Option<Vec3> {
+    // the hull is usually touching the wall already
+    let config = ShapeCastConfig {
+        ignore_origin_penetration: true,
+        ..ShapeCastConfig::from_max_distance(WALL_RUN_CAST_DISTANCE)
+    };
+
+    directions.iter().find_map(|&direction| {
+        let direction = Dir3::new(direction).ok()?;
+        let hit = spatial_query.cast_shape(collider, transform.translation, transform.rotation, direction, &config, filter)?;
+        (hit.normal1.y.abs() < WALL_RUN_MAX_NORMAL_Y).then_some(hit.normal1)
+    })
+}
+
+/// Looks for a walkable ledge in front of the player, too high to step onto but at most `mantle_reach` above the hull.
+/// Casts forward for the wall, up for headroom, and down onto the ledge from above it.
+/// Returns where the hull ends up standing on the ledge.
+#[allow(clippy::too_many_arguments)]
+fn find_mantle_target(
+    spatial_query: &SpatialQuery,
+    collider: &Collider,
+    transform: &Transform,
+    forward: Dir3,
+    hull: &PlayerHull,
+    hull_height: f32,
+    player_props: &LogicalPlayerProperties,
+    filter: &SpatialQueryFilter,
+) -> Option<Vec3> {
+    let cast = |origin: Vec3, direction: Dir3, max_distance: f32, ignore_origin_penetration: bool| {
+        let config = ShapeCastConfig {
+            ignore_origin_penetration,
+            ..ShapeCastConfig::from_max_distance(max_distance)
+        };
+        spatial_query.cast_shape(collider, origin, transform.rotation, direction, &config, filter)
+    };
+
+    // is there a wall in front that can't be walked up?
+    let wall = cast(transform.translation, forward, MANTLE_CAST_DISTANCE, true)?;
+    if player_props.is_walkable(wall.normal1) {
+        return None;
+    }
+
+    // is there room to rise above the highest reachable ledge and move over it?
+    let rise = hull_height + player_props.mantle_reach;
+    if cast(transform.translation, Dir3::Y, rise, true).is_some() {
+        return None;
+    }
+    let raised = transform.translation + Vec3::Y * rise;
+    let over_ledge = wall.distance + 2.0 * hull.radius();
+    if cast(raised, forward, over_ledge, true).is_some() {
+        return None;
+    }
+
+    // find the top of the ledge, it has to be higher than a step
+    let above_ledge = raised + forward * over_ledge;
+    let ledge = cast(above_ledge, Dir3::NEG_Y, rise - player_props.max_step_height, false)?;
+    if ledge.distance <= 0.0 || !player_props.is_walkable(ledge.normal1) {
+        return None;
+    }
+
+    Some(above_ledge - Vec3::Y * (ledge.distance - SKIN_WIDTH))
+}
+
+/// Height of the highest waterline among the water volumes the hull overlaps.
+fn find_waterline(
+    spatial_query: &SpatialQuery,
+    waters: &Query<&ColliderAabb, With<Water>>,
+    collider: &Collider,
+    transform: &Transform,
+) -> Option<f32> {
+    let filter = SpatialQueryFilter::from_mask(GameLayer::Volume);
+
+    spatial_query.shape_intersections(collider, transform.translation, transform.rotation, &filter)
+        .into_iter()
+        .filter_map(|volume| waters.get(volume).ok())
+        .map(|aabb| aabb.max.y)
+        .reduce(f32::max)
+}
+
+/// Velocity of the ground at `point` and how fast it turns around the up axis.
+/// Colliders attached to a body move with it, static bodies and plain colliders return None.
+fn ground_motion(
+    collider_parents: &Query<&ColliderParent>,
+    grounds: &Query<(&Position, &LinearVelocity, &AngularVelocity), Without<LogicalPlayer>>,
+    ground: Entity,
+    point: Vec3,
+    delta_time: f32,
+) -> Option<(Vec3, f32)> {
+    let body = collider_parents.get(ground).map_or(ground, |collider_parent| collider_parent.get());
+    let (position, linear_velocity, angular_velocity) = grounds.get(body).ok()?;
+
+    // follow the arc for one tick instead of the tangent, so standing on a turntable doesn't drift outwards
+    let offset = point - position.0;
+    let rotated_offset = Quat::from_scaled_axis(angular_velocity.0 * delta_time) * offset;
+    let velocity = linear_velocity.0 + (rotated_offset - offset) / delta_time;
+
+    Some((velocity, angular_velocity.0.y))
+}
+
+/// Pushes the hull out of everything it overlaps and returns the corrected translation.
+fn depenetrate(
+    spatial_query: &SpatialQuery,
+    obstacles: &Query<(&Collider, &Position, &Rotation), Without<LogicalPlayer>>,
+    collider: &Collider,
+    transform: &Transform,
+    filter: &SpatialQueryFilter,
+) -> Vec3 {
+    let mut translation = transform.translation;
+
+    for _ in 0..MAX_BUMPS {
+        let mut pushed = false;
+
+        for obstacle in spatial_query.shape_intersections(collider, translation, transform.rotation, filter) {
+            let Ok((obstacle_collider, obstacle_position, obstacle_rotation)) = obstacles.get(obstacle) else {
+                continue;
+            };
+            let Ok(Some(contact)) = contact_query::contact(
+                obstacle_collider,
+                *obstacle_position,
+                *obstacle_rotation,
+                collider,
+                translation,
+                transform.rotation,
+                0.0,
+            ) else {
+                continue;
+            };
+
+            if contact.penetration > 0.0 {
+                // normal1 is in the obstacle's local space and points out of it
+                translation += (obstacle_rotation.0 * contact.normal1) * (contact.penetration + SKIN_WIDTH);
+                pushed = true;
+            }
+        }
+
+        if !pushed {
+            break;
+        }
+    }
+
+    translation
+}
@@ -0,0 +1,143 @@
+use bevy::{prelude::*, window::PrimaryWindow};
+use bevy_inspector_egui::{bevy_egui::{egui, EguiContext, EguiPlugin}, reflect_inspector::ui_for_value};
+
+use super::component::{LogicalPlayer, LogicalPlayerProperties};
+use super::preset::{MovementPreset, MovementPresetHandle, BUILTIN_PRESETS};
+
+// "Movement Tuning" window for designers: edits the live LogicalPlayerProperties using the
+// inspector ranges declared on the component, switches presets and saves the values as a preset file.
+pub struct MovementTuningPlugin;
+
+impl Plugin for MovementTuningPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<EguiPlugin>() {
+            app.add_plugins(EguiPlugin);
+        }
+
+        app
+        .init_resource::<MovementTuning>()
+        .add_systems(Update, movement_tuning_ui)
+        ;
+    }
+}
+
+#[derive(Resource)]
+pub struct MovementTuning {
+    pub preset_name: String, // name used by Load and Save, e.g. "quake" for assets/presets/quake.preset.ron
+    pub status: String, // result of the last load or save
+}
+
+impl Default for MovementTuning {
+    fn default() -> Self {
+        Self {
+            preset_name: "source".to_string(),
+            status: String::new(),
+        }
+    }
+}
+
+enum TuningAction {
+    Load(String),
+    Save(String),
+}
+
+fn movement_tuning_ui(world: &mut World) {
+    let Ok(egui_context) = world.query_filtered::<&mut EguiContext, With<PrimaryWindow>>().get_single(world) else {
+        return;
+    };
+    let mut egui_context = egui_context.clone();
+
+    let Ok(player) = world.query_filtered::<Entity, With<LogicalPlayer>>().get_single(world) else {
+        return;
+    };
+
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+    let type_registry = type_registry.read();
+
+    world.resource_scope(|world, mut tuning: Mut<MovementTuning>| {
+        let mut action = None;
+
+        egui::Window::new("Movement Tuning").show(egui_context.get_mut(), |ui| {
+            ui.horizontal(|ui| {
+                for name in BUILTIN_PRESETS {
+                    if ui.button(name).clicked() {
+                        tuning.preset_name = name.to_string();
+                        action = Some(TuningAction::Load(name.to_string()));
+                    }
+                }
+            });
+
+            ui.horizontal(|ui| {
+                ui.label("Preset");
+                ui.text_edit_singleline(&mut tuning.preset_name);
+                if ui.button("Load").clicked() {
+                    action = Some(TuningAction::Load(tuning.preset_name.trim().to_string()));
+                }
+                if ui.button("Save").clicked() {
+                    action = Some(TuningAction::Save(tuning.preset_name.trim().to_string()));
+                }
+            });
+
+            if !tuning.status.is_empty() {
+                ui.label(&tuning.status);
+            }
+
+            ui.separator();
+
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                if let Some(mut player_props) = world.get_mut::<LogicalPlayerProperties>(player) {
+                    // only flag the component as changed when a value was actually edited
+                    if ui_for_value(player_props.bypass_change_detection(), ui, &type_registry) {
+                        player_props.set_changed();
+                    }
+                }
+            });
+        });
+
+        let Some(action) = action else {
+            return;
+        };
+
+        let name = match &action {
+            TuningAction::Load(name) | TuningAction::Save(name) => name.clone(),
+        };
+        if name.is_empty() || name.contains(['/', '\\', '.']) {
+            tuning.status = format!("{name:?} is not a valid preset name");
+            return;
+        }
+
+        let path = MovementPreset::path(&name);
+
+        match action {
+            TuningAction::Save(_) => {
+                let Some(player_props) = world.get::<LogicalPlayerProperties>(player) else {
+                    return;
+                };
+                let preset = MovementPreset { properties: player_props.clone() };
+                match preset.save(&name) {
+                    Ok(file_path) => tuning.status = format!("saved to {}", file_path.display()),
+                    Err(error) => {
+                        tuning.status = error.to_string();
+                        return;
+                    },
+                }
+
+                // the current preset is already followed, reapplying its stale asset would undo the edits
+                let followed = world.get::<MovementPresetHandle>(player)
+                    .and_then(|preset_handle| preset_handle.0.path().map(|followed_path| followed_path.path().to_path_buf()));
+                if followed.as_deref() == Some(std::path::Path::new(&path)) {
+                    return;
+                }
+            },
+            TuningAction::Load(_) => {
+                tuning.status = format!("loaded {name}");
+                // pick up edits made on disk while the file isn't watched
+                world.resource::<AssetServer>().reload(path.clone());
+            },
+        }
+
+        // the player follows the loaded or saved preset, so later edits to its file hot reload
+        let handle = world.resource::<AssetServer>().load(path);
+        world.entity_mut(player).insert(MovementPresetHandle(handle));
+    });
+}
@@ -1,6 +1,14 @@
 use avian3d::prelude::{LinearVelocity, Position, RigidBody};
 use bevy::prelude::*;
 
+use crate::environment::platform::MovingPlatform;
+use crate::environment::volume::{Ladder, Water};
+use crate::player::component::{GroundKind, GroundState, Health, LogicalPlayer, LogicalPlayerController, LogicalPlayerProperties, MoveMode, PlayerBody, PlayerControls, PlayerInput, PreviousTranslation, RenderPlayer, Stamina, WaterState};
+use crate::player::controls::InputBinding;
+use crate::player::hull::PlayerHull;
+use crate::player::preset::MovementPresetHandle;
+use crate::weapon::component::Weapon;
+
 pub struct RegisterPlugin;
 
 impl Plugin for RegisterPlugin {
@@ -9,6 +17,30 @@ impl Plugin for RegisterPlugin {
         .register_type::<RigidBody>()
         .register_type::<LinearVelocity>()
         .register_type::<Position>()
+
+        .register_type::<LogicalPlayer>()
+        .register_type::<LogicalPlayerProperties>()
+        .register_type::<LogicalPlayerController>()
+        .register_type::<MoveMode>()
+        .register_type::<PlayerBody>()
+        .register_type::<PlayerHull>()
+        .register_type::<PlayerControls>()
+        .register_type::<InputBinding>()
+        .register_type::<PlayerInput>()
+        .register_type::<Stamina>()
+        .register_type::<Health>()
+        .register_type::<GroundState>()
+        .register_type::<GroundKind>()
+        .register_type::<WaterState>()
+        .register_type::<PreviousTranslation>()
+        .register_type::<RenderPlayer>()
+        .register_type::<MovementPresetHandle>()
+
+        .register_type::<MovingPlatform>()
+        .register_type::<Ladder>()
+        .register_type::<Water>()
+
+        .register_type::<Weapon>()
         ;
     }
-}
\ No newline at end of file
+}
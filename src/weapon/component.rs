@@ -0,0 +1,110 @@
+use bevy::prelude::*;
+use bevy_inspector_egui::prelude::*;
+
+// Hitscan weapon held by the RenderPlayer camera, tuning values and the magazine and timing state
+#[derive(Component, Reflect, InspectorOptions, Clone, Debug, PartialEq)]
+#[reflect(Component, InspectorOptions)]
+pub struct Weapon {
+    #[inspector(min = 0.1, max = 30.0)]
+    pub fire_rate: f32, // shots per second
+    // keeps firing while the trigger is held, otherwise every shot needs its own press
+    pub automatic: bool,
+    #[inspector(min = 1, max = 200)]
+    pub magazine_size: u32,
+    #[inspector(min = 0.0, max = 10.0)]
+    pub reload_time: f32,
+
+    #[inspector(min = 0.0, max = 1000.0)]
+    pub damage: f32, // per pellet
+    #[inspector(min = 0.0, max = 1000.0)]
+    pub range: f32,
+    // damage starts dropping at falloff_start and reaches damage * falloff_min_factor at falloff_end
+    #[inspector(min = 0.0, max = 1000.0)]
+    pub falloff_start: f32,
+    #[inspector(min = 0.0, max = 1000.0)]
+    pub falloff_end: f32,
+    #[inspector(min = 0.0, max = 1.0)]
+    pub falloff_min_factor: f32,
+
+    // rays per shot, spread over a cone of half angle spread
+    #[inspector(min = 1, max = 32)]
+    pub pellets: u32,
+    #[inspector(min = 0.0, max = 0.5)]
+    pub spread: f32, // radians
+
+    pub ammo: u32,
+    pub cooldown: f32, // seconds until the next shot
+    pub reload_timer: f32, // seconds left in the current reload, zero when not reloading
+    pub fire_held: bool,
+}
+
+impl Default for Weapon {
+    fn default() -> Self {
+        Self::rifle()
+    }
+}
+
+impl Weapon {
+    pub fn rifle() -> Self {
+        Self {
+            fire_rate: 10.0,
+            automatic: true,
+            magazine_size: 30,
+            reload_time: 2.0,
+            damage: 30.0,
+            range: 200.0,
+            falloff_start: 30.0,
+            falloff_end: 100.0,
+            falloff_min_factor: 0.6,
+            pellets: 1,
+            spread: 0.0,
+            ammo: 30,
+            cooldown: 0.0,
+            reload_timer: 0.0,
+            fire_held: false,
+        }
+    }
+
+    pub fn shotgun() -> Self {
+        Self {
+            fire_rate: 1.2,
+            automatic: false,
+            magazine_size: 6,
+            reload_time: 3.0,
+            damage: 12.0,
+            range: 60.0,
+            falloff_start: 5.0,
+            falloff_end: 25.0,
+            falloff_min_factor: 0.2,
+            pellets: 9,
+            spread: 0.08,
+            ammo: 6,
+            ..Self::rifle()
+        }
+    }
+
+    pub fn is_reloading(&self) -> bool {
+        self.reload_timer > 0.0
+    }
+
+    pub fn start_reload(&mut self) {
+        if self.reload_time > 0.0 {
+            self.reload_timer = self.reload_time;
+        } else {
+            self.ammo = self.magazine_size;
+        }
+    }
+
+    // Damage of a single pellet hitting at the given distance
+    pub fn damage_at(&self, distance: f32) -> f32 {
+        let range = self.falloff_end - self.falloff_start;
+        let fraction = if range > 0.0 {
+            ((distance - self.falloff_start) / range).clamp(0.0, 1.0)
+        } else if distance > self.falloff_start {
+            1.0
+        } else {
+            0.0
+        };
+        self.damage * (1.0 + (self.falloff_min_factor - 1.0) * fraction)
+    }
+}
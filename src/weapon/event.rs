@@ -0,0 +1,15 @@
+use bevy::prelude::*;
+
+// Sent for every pellet that hits something, so damage, decals and impact effects can react
+#[derive(Event, Debug, Clone, Copy, PartialEq)]
+pub struct HitEvent {
+    // the logical player that fired
+    pub shooter: Entity,
+    // the collider that was hit
+    pub entity: Entity,
+    pub point: Vec3,
+    pub normal: Vec3,
+    pub distance: f32,
+    // damage after falloff
+    pub damage: f32,
+}
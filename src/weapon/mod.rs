@@ -0,0 +1,23 @@
+use bevy::prelude::*;
+
+use event::HitEvent;
+use system::{equip_render_player, weapon_fire};
+
+use crate::player::system::{consume_tick_input, player_move};
+
+pub mod component;
+pub mod event;
+pub mod system;
+
+// Hitscan weapons, fired at the movement tick rate from the logical player's view
+pub struct WeaponPlugin;
+
+impl Plugin for WeaponPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_event::<HitEvent>()
+        .add_systems(Update, equip_render_player)
+        .add_systems(FixedUpdate, weapon_fire.after(player_move).before(consume_tick_input))
+        ;
+    }
+}
@@ -0,0 +1,108 @@
+use avian3d::prelude::*;
+use bevy::prelude::*;
+
+use crate::environment::volume::GameLayer;
+use crate::player::component::{LogicalPlayer, LogicalPlayerController, LogicalPlayerProperties, PlayerInput, RenderPlayer};
+use crate::player::demo::DemoPlayback;
+use crate::player::hull::PlayerHull;
+use super::component::Weapon;
+use super::event::HitEvent;
+
+// Turn between successive pellets of a shot, spreads any number of them evenly over the cone
+const GOLDEN_ANGLE: f32 = 2.399_963;
+
+// hands every new RenderPlayer without a weapon the default one
+pub fn equip_render_player(
+    mut commands: Commands,
+    query: Query<Entity, (Added<RenderPlayer>, Without<Weapon>)>
+) {
+    for entity in &query {
+        commands.entity(entity).insert(Weapon::default());
+    }
+}
+
+// fires and reloads the weapons on RenderPlayers, shots go from the logical player's eye along its view
+// demos only record movement, so playing one back doesn't fire
+pub fn weapon_fire(
+    time: Res<Time>,
+    playback: Option<Res<DemoPlayback>>,
+    mut hit_events: EventWriter<HitEvent>,
+    spatial_query: SpatialQuery,
+    mut weapon_query: Query<(&mut Weapon, &RenderPlayer)>,
+    logical_query: Query<(&Transform, &LogicalPlayerController, &LogicalPlayerProperties, &PlayerHull, &PlayerInput), With<LogicalPlayer>>
+) {
+    if playback.is_some() {
+        return;
+    }
+
+    let delta_time = time.delta_secs();
+
+    for (mut weapon, render_player) in &mut weapon_query {
+        let shooter = render_player.logical_entity;
+        let Ok((transform, logical_controller, player_props, hull, player_input)) = logical_query.get(shooter) else {
+            continue;
+        };
+
+        // a press is the first tick fire is held
+        let fire_pressed = player_input.fire && !weapon.fire_held;
+        weapon.fire_held = player_input.fire;
+
+        if weapon.is_reloading() {
+            weapon.reload_timer -= delta_time;
+            if weapon.reload_timer <= 0.0 {
+                weapon.reload_timer = 0.0;
+                weapon.ammo = weapon.magazine_size;
+            }
+        } else if player_input.reload && weapon.ammo < weapon.magazine_size {
+            weapon.start_reload();
+        }
+
+        // the cooldown carries over between shots, so the fire rate isn't rounded up to whole ticks
+        weapon.cooldown -= delta_time;
+        let wants_fire = if weapon.automatic { player_input.fire } else { fire_pressed };
+        if wants_fire && weapon.cooldown <= 0.0 && !weapon.is_reloading() {
+            if weapon.ammo == 0 {
+                // firing an empty magazine reloads it
+                weapon.start_reload();
+            } else {
+                weapon.ammo -= 1;
+                weapon.cooldown += 1.0 / weapon.fire_rate;
+
+                let eye = transform.translation + Vec3::Y * hull.eye_offset(player_props.hull_height(logical_controller.crouched));
+                let view = Quat::from_euler(EulerRot::YXZ, logical_controller.yaw, logical_controller.pitch, 0.0);
+                // volumes are sensors on their own layer, shots only hit solid colliders
+                let filter = SpatialQueryFilter::from_mask(GameLayer::Default).with_excluded_entities([shooter]);
+
+                for direction in pellet_directions(view, weapon.pellets, weapon.spread) {
+                    let Some(hit) = spatial_query.cast_ray(eye, direction, weapon.range, true, &filter) else {
+                        continue;
+                    };
+                    hit_events.send(HitEvent {
+                        shooter,
+                        entity: hit.entity,
+                        point: eye + direction * hit.distance,
+                        normal: hit.normal,
+                        distance: hit.distance,
+                        damage: weapon.damage_at(hit.distance),
+                    });
+                }
+            }
+        }
+        // no banking shots while the trigger is up
+        weapon.cooldown = f32::max(weapon.cooldown, 0.0);
+    }
+}
+
+/// Directions of the pellets of one shot seen through `view`.
+/// The first pellet goes straight ahead, the rest fill the cone of half angle `spread` in a fixed
+/// sunflower pattern, so the spread is the same every shot and doesn't need a random number generator.
+fn pellet_directions(view: Quat, pellets: u32, spread: f32) -> impl Iterator<Item = Dir3> {
+    (0..pellets).map(move |pellet| {
+        if pellet == 0 {
+            return view * Dir3::NEG_Z;
+        }
+        let angle = spread * (pellet as f32 / (pellets - 1) as f32).sqrt();
+        let around = pellet as f32 * GOLDEN_ANGLE;
+        view * Quat::from_rotation_z(around) * Quat::from_rotation_x(angle) * Dir3::NEG_Z
+    })
+}
@@ -0,0 +1,114 @@
+// Headless app harness for the player controller.
+// Runs without a window, GPU or input devices: PlayerInput is scripted and every app update is exactly one tick.
+#![allow(dead_code)]
+
+use std::time::Duration;
+
+use avian3d::prelude::*;
+use bevy::{prelude::*, scene::ScenePlugin, time::TimeUpdateStrategy};
+use my_crate::player::component::{LogicalPlayer, LogicalPlayerController, MoveMode, PlayerInput, PreviousTranslation};
+use my_crate::player::PlayerPlugin;
+
+pub const TICK_RATE: f64 = 64.0;
+
+// The controller counts as grounded anywhere within 0.125 of the floor, so resting heights vary by that much
+pub const GROUND_TOLERANCE: f32 = 0.15;
+
+/// Builds an app with a flat floor whose top is at y = 0 and a logical player in the `Ground` move mode.
+pub fn player_app() -> App {
+    player_app_with(|_| {})
+}
+
+/// Same as `player_app`, `setup` can add plugins and systems before the first update.
+pub fn player_app_with(setup: impl FnOnce(&mut App)) -> App {
+    let mut app = App::new();
+    app
+    .add_plugins((
+        MinimalPlugins,
+        TransformPlugin,
+        AssetPlugin::default(),
+        ScenePlugin,
+        PhysicsPlugins::default(),
+        PlayerPlugin { tick_rate: TICK_RATE, headless: true, movement_preset: None, ..default() },
+    ))
+    .init_asset::<Mesh>()
+    .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(1.0 / TICK_RATE)))
+    .add_systems(Startup, spawn_floor)
+    ;
+    setup(&mut app);
+
+    // the first update runs Startup and starts the clocks, no tick runs yet
+    app.update();
+
+    let player = logical_player(&mut app);
+    app.world_mut().get_mut::<LogicalPlayerController>(player).unwrap().move_mode = MoveMode::Ground;
+
+    app
+}
+
+fn spawn_floor(mut commands: Commands) {
+    commands.spawn((
+        Transform::from_xyz(0.0, -0.5, 0.0),
+        RigidBody::Static,
+        Collider::cuboid(100.0, 1.0, 100.0),
+    ))
+    .insert(Name::new("Floor"));
+}
+
+pub fn logical_player(app: &mut App) -> Entity {
+    app.world_mut()
+        .query_filtered::<Entity, With<LogicalPlayer>>()
+        .single(app.world())
+}
+
+/// Feeds the same input into the player for the given number of ticks.
+pub fn run_ticks(app: &mut App, input: &PlayerInput, ticks: usize) {
+    let player = logical_player(app);
+    for _ in 0..ticks {
+        *app.world_mut().get_mut::<PlayerInput>(player).unwrap() = input.clone();
+        app.update();
+    }
+}
+
+/// Lets the player fall from the spawn point and settle on the floor.
+pub fn land(app: &mut App) {
+    run_ticks(app, &PlayerInput::default(), 2 * TICK_RATE as usize);
+}
+
+pub fn player_transform(app: &mut App) -> Transform {
+    let player = logical_player(app);
+    *app.world().get::<Transform>(player).unwrap()
+}
+
+pub fn player_velocity(app: &mut App) -> Vec3 {
+    let player = logical_player(app);
+    app.world().get::<LinearVelocity>(player).unwrap().0
+}
+
+pub fn move_mode(app: &mut App) -> MoveMode {
+    let player = logical_player(app);
+    app.world().get::<LogicalPlayerController>(player).unwrap().move_mode
+}
+
+/// Every `E` sent since it was added, filled by `collect_events::<E>`.
+#[derive(Resource)]
+pub struct EventLog<E: Event>(pub Vec<E>);
+
+impl<E: Event> Default for EventLog<E> {
+    fn default() -> Self {
+        Self(Vec::new())
+    }
+}
+
+pub fn collect_events<E: Event + Copy>(mut events: EventReader<E>, mut log: ResMut<EventLog<E>>) {
+    log.0.extend(events.read().copied());
+}
+
+/// Moves the player without interpolating from the old position and sets its velocity.
+pub fn teleport(app: &mut App, translation: Vec3, velocity: Vec3) {
+    let player = logical_player(app);
+    let mut player_entity = app.world_mut().entity_mut(player);
+    player_entity.get_mut::<Transform>().unwrap().translation = translation;
+    player_entity.get_mut::<PreviousTranslation>().unwrap().0 = translation;
+    player_entity.get_mut::<LinearVelocity>().unwrap().0 = velocity;
+}
@@ -0,0 +1,101 @@
+mod common;
+
+use bevy::{input::mouse::MouseWheel, prelude::*};
+use common::{collect_events, logical_player, player_app_with, EventLog};
+use my_crate::player::component::PlayerControls;
+use my_crate::player::controls::{capture_rebinding, ControlAction, InputBinding, Rebinding};
+use my_crate::player::event::RebindEvent;
+
+// player app that captures rebindings, the inputs are pressed on the ButtonInput resources directly
+// since nothing clears them without the InputPlugin. Leaves out saving, which writes the user's config.
+fn rebind_app() -> App {
+    player_app_with(|app| {
+        app
+        .init_resource::<ButtonInput<KeyCode>>()
+        .init_resource::<ButtonInput<MouseButton>>()
+        .add_event::<MouseWheel>()
+        .init_resource::<EventLog<RebindEvent>>()
+        .add_systems(PreUpdate, capture_rebinding)
+        .add_systems(PostUpdate, collect_events::<RebindEvent>)
+        ;
+    })
+}
+
+#[test]
+fn default_controls_have_no_conflicts() {
+    assert!(PlayerControls::default().conflicts().is_empty());
+}
+
+#[test]
+fn rebinding_to_a_free_input() {
+    let mut controls = PlayerControls::default();
+
+    let swapped = controls.rebind(ControlAction::Jump, InputBinding::WheelDown);
+
+    assert_eq!(swapped, None);
+    assert_eq!(controls.key_jump, InputBinding::WheelDown);
+}
+
+#[test]
+fn rebinding_to_a_used_input_swaps_bindings() {
+    let mut controls = PlayerControls::default();
+
+    // ZQSD layout: forward moves to Z, which nothing uses, then left takes Q from up
+    controls.rebind(ControlAction::Forward, InputBinding::Key(KeyCode::KeyZ));
+    let swapped = controls.rebind(ControlAction::Left, InputBinding::Key(KeyCode::KeyQ));
+
+    assert_eq!(swapped, Some(ControlAction::Up));
+    assert_eq!(controls.key_left, InputBinding::Key(KeyCode::KeyQ));
+    assert_eq!(controls.key_up, InputBinding::Key(KeyCode::KeyA));
+    assert!(controls.conflicts().is_empty());
+}
+
+#[test]
+fn controls_round_trip_through_ron() {
+    let mut controls = PlayerControls::default();
+    controls.rebind(ControlAction::Crouch, InputBinding::Mouse(MouseButton::Right));
+
+    let loaded = PlayerControls::from_ron_str(&controls.to_ron_string().unwrap()).unwrap();
+
+    assert_eq!(loaded, controls);
+}
+
+#[test]
+fn missing_bindings_fall_back_to_defaults() {
+    let loaded = PlayerControls::from_ron_str("(key_jump: WheelDown)").unwrap();
+
+    assert_eq!(loaded.key_jump, InputBinding::WheelDown);
+    assert_eq!(loaded.key_forward, PlayerControls::default().key_forward);
+}
+
+#[test]
+fn rebinding_captures_the_next_pressed_key() {
+    let mut app = rebind_app();
+    let player = logical_player(&mut app);
+
+    app.insert_resource(Rebinding { action: ControlAction::Jump });
+    app.world_mut().resource_mut::<ButtonInput<KeyCode>>().press(KeyCode::KeyJ);
+    app.update();
+
+    assert_eq!(app.world().get::<PlayerControls>(player).unwrap().key_jump, InputBinding::Key(KeyCode::KeyJ));
+    assert_eq!(app.world().resource::<EventLog<RebindEvent>>().0, [RebindEvent {
+        action: ControlAction::Jump,
+        binding: InputBinding::Key(KeyCode::KeyJ),
+        swapped: None,
+    }]);
+    assert!(app.world().get_resource::<Rebinding>().is_none());
+}
+
+#[test]
+fn escape_cancels_rebinding() {
+    let mut app = rebind_app();
+    let player = logical_player(&mut app);
+
+    app.insert_resource(Rebinding { action: ControlAction::Jump });
+    app.world_mut().resource_mut::<ButtonInput<KeyCode>>().press(KeyCode::Escape);
+    app.update();
+
+    assert_eq!(app.world().get::<PlayerControls>(player).unwrap(), &PlayerControls::default());
+    assert!(app.world().resource::<EventLog<RebindEvent>>().0.is_empty());
+    assert!(app.world().get_resource::<Rebinding>().is_none());
+}
@@ -0,0 +1,86 @@
+mod common;
+
+use bevy::prelude::*;
+use common::*;
+use my_crate::player::component::PlayerInput;
+use my_crate::player::demo::{Demo, DemoError, DemoPlayback, DemoRecording};
+
+// Strafes in a circle, jumps now and then and crouches for a bit
+fn scripted_input(tick: usize) -> PlayerInput {
+    PlayerInput {
+        movement: Vec3::new(1.0, 0.0, 1.0),
+        yaw: 0.01,
+        pitch: if tick < 20 { -0.005 } else { 0.0 },
+        jump: tick % 40 == 0,
+        crouch: (60..80).contains(&tick),
+        ..default()
+    }
+}
+
+fn record(app: &mut App, ticks: usize) -> Demo {
+    app.insert_resource(DemoRecording::default());
+    for tick in 0..ticks {
+        run_ticks(app, &scripted_input(tick), 1);
+    }
+    app.world_mut().remove_resource::<DemoRecording>().unwrap().demo.unwrap()
+}
+
+#[test]
+fn replay_reproduces_recorded_run() {
+    let mut recorded_app = player_app();
+    land(&mut recorded_app);
+    let demo = record(&mut recorded_app, 128);
+    assert_eq!(demo.ticks.len(), 128);
+
+    // round trip through the file format
+    let demo = Demo::from_ron_str(&demo.to_ron_string().unwrap()).unwrap();
+
+    let mut replay_app = player_app();
+    land(&mut replay_app);
+    replay_app.insert_resource(DemoPlayback::new(demo));
+    run_ticks(&mut replay_app, &PlayerInput::default(), 128);
+
+    assert_eq!(player_transform(&mut replay_app), player_transform(&mut recorded_app));
+    assert_eq!(player_velocity(&mut replay_app), player_velocity(&mut recorded_app));
+}
+
+#[test]
+fn playback_ends_after_last_tick() {
+    let mut app = player_app();
+    land(&mut app);
+    let demo = record(&mut app, 10);
+
+    app.insert_resource(DemoPlayback::new(demo));
+    run_ticks(&mut app, &PlayerInput::default(), 11);
+
+    assert!(app.world().get_resource::<DemoPlayback>().is_none());
+}
+
+#[test]
+fn rejects_other_versions() {
+    let mut app = player_app();
+    let mut demo = record(&mut app, 1);
+    demo.version += 1;
+
+    let result = Demo::from_ron_str(&demo.to_ron_string().unwrap());
+
+    assert!(matches!(result, Err(DemoError::UnsupportedVersion(_))));
+}
+
+#[test]
+fn refuses_demos_recorded_at_another_tick_rate() {
+    let mut app = player_app();
+    land(&mut app);
+    let mut demo = record(&mut app, 10);
+    demo.tick_rate = TICK_RATE / 2.0;
+    assert!(matches!(demo.check_tick_rate(TICK_RATE), Err(DemoError::TickRateMismatch { .. })));
+
+    let translation = Vec3::new(3.0, 1.0, 3.0);
+    teleport(&mut app, translation, Vec3::ZERO);
+    app.insert_resource(DemoPlayback::new(demo));
+    run_ticks(&mut app, &PlayerInput::default(), 1);
+
+    // nothing restored or played
+    assert!(app.world().get_resource::<DemoPlayback>().is_none());
+    assert!(player_transform(&mut app).translation.distance(translation) < 0.5);
+}
@@ -0,0 +1,114 @@
+mod common;
+
+use bevy::input::gamepad::{GamepadConnection, GamepadConnectionEvent, RawGamepadAxisChangedEvent, RawGamepadButtonChangedEvent, RawGamepadEvent};
+use bevy::input::{InputPlugin, InputSystem};
+use bevy::prelude::*;
+use common::{logical_player, player_app_with};
+use my_crate::player::component::{LogicalPlayerController, PlayerControls, PlayerInput};
+use my_crate::player::gamepad::{apply_radial_deadzone, apply_response_curve, player_gamepad_input};
+use my_crate::player::system::player_input;
+use my_crate::CursorLocked;
+
+// player app with the input systems the headless PlayerPlugin leaves out and a connected gamepad
+fn gamepad_app() -> (App, Entity) {
+    let mut app = player_app_with(|app| {
+        app
+        .add_plugins(InputPlugin)
+        .insert_resource(CursorLocked(true))
+        .add_systems(PreUpdate, (player_input, player_gamepad_input).chain().after(InputSystem))
+        ;
+    });
+
+    let gamepad = app.world_mut().spawn_empty().id();
+    app.world_mut().send_event(GamepadConnectionEvent::new(gamepad, GamepadConnection::Connected {
+        name: "Test Gamepad".to_string(),
+        vendor_id: None,
+        product_id: None,
+    }));
+    app.update();
+
+    (app, gamepad)
+}
+
+fn move_axis(app: &mut App, gamepad: Entity, axis: GamepadAxis, value: f32) {
+    app.world_mut().send_event(RawGamepadEvent::Axis(RawGamepadAxisChangedEvent::new(gamepad, axis, value)));
+}
+
+fn press_button(app: &mut App, gamepad: Entity, button: GamepadButton) {
+    app.world_mut().send_event(RawGamepadEvent::Button(RawGamepadButtonChangedEvent::new(gamepad, button, 1.0)));
+}
+
+fn controller_yaw(app: &mut App) -> f32 {
+    let player = logical_player(app);
+    app.world().get::<LogicalPlayerController>(player).unwrap().yaw
+}
+
+#[test]
+fn radial_deadzone_rescales_to_full_range() {
+    assert_eq!(apply_radial_deadzone(Vec2::new(0.05, 0.05), 0.1), Vec2::ZERO);
+    assert_eq!(apply_radial_deadzone(Vec2::Y, 0.1), Vec2::Y);
+
+    let stick = apply_radial_deadzone(Vec2::new(0.0, -0.55), 0.1);
+    assert!((stick - Vec2::new(0.0, -0.5)).length() < 1e-6, "got {stick}");
+
+    // the response curve keeps the direction and full tilt
+    assert_eq!(apply_response_curve(Vec2::X, 2.0), Vec2::X);
+    assert!((apply_response_curve(Vec2::new(0.0, 0.5), 2.0) - Vec2::new(0.0, 0.25)).length() < 1e-6);
+}
+
+#[test]
+fn left_stick_keeps_analog_magnitude() {
+    let (mut app, gamepad) = gamepad_app();
+    let player = logical_player(&mut app);
+
+    move_axis(&mut app, gamepad, GamepadAxis::LeftStickY, 0.5);
+    app.update();
+
+    let deadzone = app.world().get::<PlayerControls>(player).unwrap().stick_move_deadzone;
+    let movement = app.world().get::<PlayerInput>(player).unwrap().movement;
+    let expected = (0.5 - deadzone) / (1.0 - deadzone);
+    assert!((movement - Vec3::new(0.0, 0.0, expected)).length() < 1e-5, "got {movement}");
+}
+
+#[test]
+fn right_stick_turns_with_aim_acceleration() {
+    let (mut app, gamepad) = gamepad_app();
+    let player = logical_player(&mut app);
+    let controls = app.world().get::<PlayerControls>(player).unwrap().clone();
+    let dt = 1.0 / common::TICK_RATE as f32;
+
+    move_axis(&mut app, gamepad, GamepadAxis::RightStickX, 1.0);
+    let yaw = controller_yaw(&mut app);
+    app.update();
+
+    // full right turns to negative yaw at the base rate until the stick has been held long enough
+    let base_turn = controller_yaw(&mut app) - yaw;
+    assert!((base_turn + controls.stick_look_sensitivity * dt).abs() < 1e-5, "turned {base_turn}");
+
+    let hold = controls.stick_aim_acceleration_delay + controls.stick_aim_acceleration_time;
+    for _ in 0..(hold / dt).ceil() as usize {
+        app.update();
+    }
+
+    let yaw = controller_yaw(&mut app);
+    app.update();
+    let accelerated_turn = controller_yaw(&mut app) - yaw;
+    let expected = base_turn * (1.0 + controls.stick_aim_acceleration);
+    assert!((accelerated_turn - expected).abs() < 1e-5, "turned {accelerated_turn}, expected {expected}");
+}
+
+#[test]
+fn buttons_follow_pad_bindings() {
+    let (mut app, gamepad) = gamepad_app();
+    let player = logical_player(&mut app);
+    let controls = app.world().get::<PlayerControls>(player).unwrap().clone();
+
+    press_button(&mut app, gamepad, controls.pad_jump);
+    press_button(&mut app, gamepad, controls.pad_sprint);
+    app.update();
+
+    let player_input = app.world().get::<PlayerInput>(player).unwrap();
+    assert!(player_input.jump);
+    assert!(player_input.sprint);
+    assert!(!player_input.crouch);
+}
@@ -0,0 +1,98 @@
+mod common;
+
+use avian3d::prelude::*;
+use bevy::prelude::*;
+use common::*;
+use my_crate::player::component::{GroundKind, GroundState, LogicalPlayerProperties, PlayerInput};
+use my_crate::player::hull::{HullError, PlayerHull, SpawnPlayerHull};
+use my_crate::player::PlayerPlugin;
+
+#[test]
+fn hull_offsets_follow_the_height() {
+    for hull in [
+        PlayerHull::Cylinder { radius: 0.5 },
+        PlayerHull::Capsule { radius: 0.4 },
+        PlayerHull::Box { half_extent: 0.5 },
+    ] {
+        assert_eq!(hull.foot_offset(2.0), 1.0);
+        assert_eq!(hull.eye_offset(2.0), 0.5);
+    }
+}
+
+#[test]
+fn invalid_hulls_are_rejected() {
+    let props = LogicalPlayerProperties { crouch_height: 1.0, ..default() };
+
+    assert_eq!(PlayerHull::Cylinder { radius: 0.0 }.validate(&props), Err(HullError::InvalidSize(0.0)));
+    assert_eq!(
+        PlayerHull::Capsule { radius: 0.6 }.validate(&props),
+        Err(HullError::TooShortForCapsule { height: 1.0, radius: 0.6 })
+    );
+    assert_eq!(PlayerHull::Capsule { radius: 0.4 }.validate(&props), Ok(()));
+    assert_eq!(PlayerHull::Box { half_extent: 0.6 }.validate(&props), Ok(()));
+
+    let props = LogicalPlayerProperties { crouch_height: -1.0, ..default() };
+    assert_eq!(PlayerHull::default().validate(&props), Err(HullError::InvalidHeight(-1.0)));
+}
+
+#[test]
+fn capsule_player_lands_and_walks() {
+    let mut app = player_app();
+    let player = logical_player(&mut app);
+    app.world_mut().entity_mut(player).insert(PlayerHull::Capsule { radius: 0.4 });
+    land(&mut app);
+
+    assert_eq!(app.world().get::<GroundState>(player).unwrap().kind, GroundKind::Walkable);
+
+    let props = LogicalPlayerProperties::default();
+    let feet = player_transform(&mut app).translation.y - props.stand_height / 2.0;
+    assert!(feet > -0.01 && feet < GROUND_TOLERANCE, "resting with feet at {feet}");
+
+    let forward = PlayerInput { movement: Vec3::Z, ..default() };
+    run_ticks(&mut app, &forward, TICK_RATE as usize);
+
+    let velocity = player_velocity(&mut app);
+    assert!((velocity.xz().length() - props.walk_speed).abs() < 0.05, "walking at {velocity}");
+}
+
+#[test]
+fn box_player_spawns_from_the_plugin() {
+    let mut app = player_app_with(|app| {
+        app.insert_resource(SpawnPlayerHull(PlayerHull::Box { half_extent: 0.4 }));
+    });
+    land(&mut app);
+
+    let player = logical_player(&mut app);
+    assert_eq!(*app.world().get::<PlayerHull>(player).unwrap(), PlayerHull::Box { half_extent: 0.4 });
+    assert_eq!(app.world().get::<GroundState>(player).unwrap().kind, GroundKind::Walkable);
+}
+
+#[test]
+fn invalid_hull_keeps_the_previous_collider() {
+    let mut app = player_app();
+    land(&mut app);
+
+    let player = logical_player(&mut app);
+    let before = format!("{:?}", app.world().get::<Collider>(player).unwrap().shape_scaled().as_cylinder());
+    app.world_mut().entity_mut(player).insert(PlayerHull::Capsule { radius: 2.0 });
+    run_ticks(&mut app, &PlayerInput::default(), 2);
+
+    let after = format!("{:?}", app.world().get::<Collider>(player).unwrap().shape_scaled().as_cylinder());
+    assert_eq!(before, after);
+    assert!(app.world().get::<Collider>(player).unwrap().shape_scaled().as_cylinder().is_some());
+    assert_eq!(*app.world().get::<PlayerHull>(player).unwrap(), PlayerHull::default());
+
+    // crouching and standing back up resize the collider with the previous hull
+    let crouch = PlayerInput { crouch: true, ..default() };
+    run_ticks(&mut app, &crouch, TICK_RATE as usize / 2);
+    assert!(app.world().get::<Collider>(player).unwrap().shape_scaled().as_cylinder().is_some());
+
+    run_ticks(&mut app, &PlayerInput::default(), TICK_RATE as usize / 2);
+    let uncrouched = format!("{:?}", app.world().get::<Collider>(player).unwrap().shape_scaled().as_cylinder());
+    assert_eq!(before, uncrouched);
+}
+
+#[test]
+fn default_plugin_hull_is_a_cylinder() {
+    assert_eq!(PlayerPlugin::default().hull, PlayerHull::Cylinder { radius: 0.5 });
+}
@@ -0,0 +1,236 @@
+mod common;
+
+use avian3d::prelude::*;
+use bevy::prelude::*;
+use common::*;
+use my_crate::player::component::{GroundState, LogicalPlayerController, LogicalPlayerProperties, PlayerInput};
+
+fn set_props(app: &mut App, set: impl FnOnce(&mut LogicalPlayerProperties)) {
+    let player = logical_player(app);
+    set(&mut app.world_mut().get_mut::<LogicalPlayerProperties>(player).unwrap());
+}
+
+fn is_grounded(app: &mut App) -> bool {
+    let player = logical_player(app);
+    app.world().get::<GroundState>(player).unwrap().is_grounded()
+}
+
+fn hold_jump() -> PlayerInput {
+    PlayerInput { jump: true, ..default() }
+}
+
+/// Runs ticks until the player is falling within `height` of the floor, at most `max_ticks`.
+/// Falling less than a tick's distance into that band keeps the player clear of the ground probe.
+fn fall_until(app: &mut App, height: f32, max_ticks: usize) {
+    let props = LogicalPlayerProperties::default();
+    for _ in 0..max_ticks {
+        let feet = player_transform(app).translation.y - props.stand_height / 2.0;
+        if player_velocity(app).y < 0.0 && feet < height {
+            return;
+        }
+        run_ticks(app, &PlayerInput::default(), 1);
+    }
+    panic!("never fell below {height}");
+}
+
+#[test]
+fn holding_jump_bhops_with_auto_bhop() {
+    let mut app = player_app();
+    land(&mut app);
+
+    let mut landings = 0;
+    let mut was_grounded = true;
+    for _ in 0..3 * TICK_RATE as usize {
+        run_ticks(&mut app, &hold_jump(), 1);
+        let grounded = is_grounded(&mut app);
+        if grounded && !was_grounded {
+            landings += 1;
+        }
+        was_grounded = grounded;
+    }
+
+    assert!(landings >= 2, "only landed {landings} times");
+}
+
+#[test]
+fn holding_jump_jumps_once_without_auto_bhop() {
+    let mut app = player_app();
+    set_props(&mut app, |props| props.auto_bhop = false);
+    land(&mut app);
+
+    run_ticks(&mut app, &hold_jump(), 2 * TICK_RATE as usize);
+
+    // landed after the first jump and stayed down
+    let mut stayed_grounded = true;
+    for _ in 0..TICK_RATE as usize {
+        run_ticks(&mut app, &hold_jump(), 1);
+        stayed_grounded &= is_grounded(&mut app);
+    }
+    assert!(stayed_grounded);
+
+    // letting go and pressing again jumps
+    run_ticks(&mut app, &PlayerInput::default(), 1);
+    run_ticks(&mut app, &hold_jump(), 1);
+    assert!(player_velocity(&mut app).y > 0.0);
+}
+
+#[test]
+fn jump_pressed_just_before_landing_is_buffered() {
+    let mut app = player_app();
+    set_props(&mut app, |props| props.auto_bhop = false);
+    land(&mut app);
+
+    run_ticks(&mut app, &hold_jump(), 1);
+    run_ticks(&mut app, &PlayerInput::default(), 1);
+    fall_until(&mut app, 0.3, 2 * TICK_RATE as usize);
+
+    // a single tick press while still in the air
+    assert!(!is_grounded(&mut app));
+    run_ticks(&mut app, &hold_jump(), 1);
+    run_ticks(&mut app, &PlayerInput::default(), 6);
+
+    assert!(player_velocity(&mut app).y > 0.0, "didn't jump again, velocity {}", player_velocity(&mut app));
+}
+
+#[test]
+fn jump_pressed_too_early_is_dropped_without_a_buffer() {
+    let mut app = player_app();
+    set_props(&mut app, |props| {
+        props.auto_bhop = false;
+        props.jump_buffer_time = 0.0;
+    });
+    land(&mut app);
+
+    run_ticks(&mut app, &hold_jump(), 1);
+    run_ticks(&mut app, &PlayerInput::default(), 1);
+    fall_until(&mut app, 0.3, 2 * TICK_RATE as usize);
+
+    assert!(!is_grounded(&mut app));
+    run_ticks(&mut app, &hold_jump(), 1);
+    run_ticks(&mut app, &PlayerInput::default(), 6);
+
+    assert!(is_grounded(&mut app));
+    assert!(player_velocity(&mut app).y <= 0.01);
+}
+
+/// Lands the player on a block whose top is at y = 1 and walks it off the -Z edge.
+/// Returns once the player has been airborne for `ticks_in_air` ticks.
+fn walk_off_ledge(app: &mut App, ticks_in_air: usize) {
+    app.world_mut().spawn((
+        Transform::from_xyz(0.0, 0.5, 0.0),
+        RigidBody::Static,
+        Collider::cuboid(4.0, 1.0, 4.0),
+    ));
+    land(app);
+
+    // yaw zero looks down -Z
+    let forward = PlayerInput { movement: Vec3::Z, ..default() };
+    let mut ticks = 0;
+    while is_grounded(app) {
+        run_ticks(app, &forward, 1);
+        ticks += 1;
+        assert!(ticks < 2 * TICK_RATE as usize, "never walked off the ledge");
+    }
+    run_ticks(app, &forward, ticks_in_air);
+}
+
+#[test]
+fn coyote_time_allows_jumping_after_walking_off_a_ledge() {
+    let mut app = player_app();
+    walk_off_ledge(&mut app, 2);
+
+    run_ticks(&mut app, &PlayerInput { movement: Vec3::Z, jump: true, ..default() }, 1);
+
+    let props = LogicalPlayerProperties::default();
+    let velocity = player_velocity(&mut app);
+    assert!(velocity.y > props.jump_impulse - 1.0, "jumping at {velocity}");
+}
+
+#[test]
+fn no_jump_after_walking_off_a_ledge_without_coyote_time() {
+    let mut app = player_app();
+    set_props(&mut app, |props| props.coyote_time = 0.0);
+    walk_off_ledge(&mut app, 2);
+
+    run_ticks(&mut app, &PlayerInput { movement: Vec3::Z, jump: true, ..default() }, 1);
+
+    assert!(player_velocity(&mut app).y < 0.0);
+}
+
+/// Jumps off the floor holding jump for `hold_ticks` and returns the highest the feet got.
+fn jump_apex(app: &mut App, hold_ticks: usize) -> f32 {
+    let props = LogicalPlayerProperties::default();
+    land(app);
+
+    let mut apex = 0.0;
+    for tick in 0..2 * TICK_RATE as usize {
+        let input = if tick < hold_ticks.max(1) { hold_jump() } else { PlayerInput::default() };
+        run_ticks(app, &input, 1);
+        apex = f32::max(apex, player_transform(app).translation.y - props.stand_height / 2.0);
+    }
+    apex
+}
+
+fn variable_jump_app() -> App {
+    let mut app = player_app();
+    set_props(&mut app, |props| {
+        props.auto_bhop = false;
+        props.jump_hold_time = 0.25;
+        props.jump_release_factor = 0.5;
+    });
+    app
+}
+
+#[test]
+fn holding_jump_jumps_higher_than_tapping_it() {
+    let tap = jump_apex(&mut variable_jump_app(), 1);
+    let hold = jump_apex(&mut variable_jump_app(), TICK_RATE as usize / 2);
+    let longer_hold = jump_apex(&mut variable_jump_app(), TICK_RATE as usize);
+
+    assert!(hold > tap + 0.5, "held to {hold}, tapped to {tap}");
+    // holding past jump_hold_time doesn't go any higher
+    assert!((longer_hold - hold).abs() < 0.05, "held longer to {longer_hold}, held to {hold}");
+}
+
+#[test]
+fn jump_height_is_fixed_by_default() {
+    let tap = jump_apex(&mut player_app(), 1);
+    let hold = jump_apex(&mut player_app(), 3);
+
+    assert!((hold - tap).abs() < 0.05, "held to {hold}, tapped to {tap}");
+}
+
+fn extra_jumps(app: &mut App) -> u8 {
+    let player = logical_player(app);
+    app.world().get::<LogicalPlayerController>(player).unwrap().extra_jumps
+}
+
+#[test]
+fn extra_jumps_are_spent_in_the_air_and_refilled_on_landing() {
+    let mut app = player_app();
+    set_props(&mut app, |props| {
+        props.auto_bhop = false;
+        props.extra_jumps = 1;
+    });
+    land(&mut app);
+    assert_eq!(extra_jumps(&mut app), 1);
+
+    let props = LogicalPlayerProperties::default();
+    run_ticks(&mut app, &hold_jump(), 1);
+    run_ticks(&mut app, &PlayerInput::default(), TICK_RATE as usize / 4);
+    assert!(player_velocity(&mut app).y < props.jump_impulse - 2.0);
+
+    // double jump
+    run_ticks(&mut app, &hold_jump(), 1);
+    assert!(player_velocity(&mut app).y > props.jump_impulse - 1.0, "double jumped at {}", player_velocity(&mut app));
+    assert_eq!(extra_jumps(&mut app), 0);
+
+    // no triple jump
+    run_ticks(&mut app, &PlayerInput::default(), TICK_RATE as usize / 4);
+    let velocity = player_velocity(&mut app).y;
+    run_ticks(&mut app, &hold_jump(), 1);
+    assert!(player_velocity(&mut app).y < velocity, "jumped a third time at {}", player_velocity(&mut app));
+
+    land(&mut app);
+    assert_eq!(extra_jumps(&mut app), 1);
+}
@@ -0,0 +1,68 @@
+mod common;
+
+use avian3d::prelude::*;
+use bevy::prelude::*;
+use common::*;
+use my_crate::player::component::{GroundKind, GroundState, LogicalPlayerProperties, PlayerBody, PlayerInput};
+
+fn kinematic_player_app() -> App {
+    let mut app = player_app();
+    let player = logical_player(&mut app);
+    app.world_mut().entity_mut(player).insert(PlayerBody::Kinematic);
+    app
+}
+
+#[test]
+fn kinematic_player_lands_and_walks() {
+    let mut app = kinematic_player_app();
+    land(&mut app);
+
+    let player = logical_player(&mut app);
+    assert_eq!(*app.world().get::<RigidBody>(player).unwrap(), RigidBody::Kinematic);
+    assert_eq!(app.world().get::<GroundState>(player).unwrap().kind, GroundKind::Walkable);
+
+    let props = LogicalPlayerProperties::default();
+    let feet = player_transform(&mut app).translation.y - props.stand_height / 2.0;
+    assert!(feet > -0.01 && feet < GROUND_TOLERANCE, "resting with feet at {feet}");
+
+    let forward = PlayerInput { movement: Vec3::Z, ..default() };
+    run_ticks(&mut app, &forward, TICK_RATE as usize);
+
+    let velocity = player_velocity(&mut app);
+    assert!((velocity.xz().length() - props.walk_speed).abs() < 0.05, "walking at {velocity}");
+}
+
+#[test]
+fn kinematic_player_slides_along_walls() {
+    let mut app = kinematic_player_app();
+    // wall whose face is the plane x = -1
+    app.world_mut().spawn((
+        Transform::from_xyz(-1.5, 2.0, 0.0),
+        RigidBody::Static,
+        Collider::cuboid(1.0, 4.0, 100.0),
+    ));
+    land(&mut app);
+
+    // yaw zero looks down -Z, so forward-left runs diagonally into the wall
+    let forward_left = PlayerInput { movement: Vec3::new(-1.0, 0.0, 1.0), ..default() };
+    run_ticks(&mut app, &forward_left, TICK_RATE as usize);
+
+    let translation = player_transform(&mut app).translation;
+    let velocity = player_velocity(&mut app);
+    assert!(translation.x - 0.5 > -1.0 - 0.01, "went into the wall to {translation}");
+    assert!(translation.z < -3.0, "stuck at {translation}");
+    assert!(velocity.x.abs() < 0.01 && velocity.z < -1.0, "sliding at {velocity}");
+}
+
+#[test]
+fn kinematic_player_is_pushed_out_of_the_floor() {
+    let mut app = kinematic_player_app();
+    land(&mut app);
+
+    let props = LogicalPlayerProperties::default();
+    teleport(&mut app, Vec3::new(0.0, props.stand_height / 2.0 - 0.2, 0.0), Vec3::ZERO);
+    run_ticks(&mut app, &PlayerInput::default(), 2);
+
+    let feet = player_transform(&mut app).translation.y - props.stand_height / 2.0;
+    assert!(feet > -0.01, "still {feet} inside the floor");
+}
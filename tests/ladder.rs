@@ -0,0 +1,153 @@
+mod common;
+
+use std::f32::consts::FRAC_PI_2;
+
+use avian3d::prelude::*;
+use bevy::{gltf::GltfExtras, prelude::*};
+use common::*;
+use my_crate::environment::volume::{tag_volume_colliders, volume_layers, Ladder, VolumeKind};
+use my_crate::player::component::{LogicalPlayerController, LogicalPlayerProperties, MoveMode, PlayerInput};
+
+/// Player standing in front of a wall whose face is the plane x = -1, with a ladder volume on it up to y = 6.
+/// The player faces the ladder.
+fn ladder_app() -> App {
+    let mut app = player_app();
+    app.world_mut().spawn((
+        Transform::from_xyz(-1.5, 5.0, 0.0),
+        RigidBody::Static,
+        Collider::cuboid(1.0, 10.0, 4.0),
+    ));
+    app.world_mut().spawn((
+        Transform::from_xyz(-0.8, 3.0, 0.0),
+        Collider::cuboid(0.4, 6.0, 1.0),
+        Sensor,
+        volume_layers(),
+        Ladder,
+    ));
+    land(&mut app);
+
+    // yaw zero looks down -Z, a quarter turn left looks down -X
+    let player = logical_player(&mut app);
+    app.world_mut().get_mut::<LogicalPlayerController>(player).unwrap().yaw = FRAC_PI_2;
+    app
+}
+
+fn climb(app: &mut App, seconds: f32) {
+    let forward = PlayerInput { movement: Vec3::Z, ..default() };
+    run_ticks(app, &forward, (seconds * TICK_RATE as f32) as usize);
+}
+
+#[test]
+fn walking_into_a_ladder_climbs_it() {
+    let mut app = ladder_app();
+    let start = player_transform(&mut app).translation;
+
+    climb(&mut app, 1.0);
+
+    assert_eq!(move_mode(&mut app), MoveMode::Ladder);
+    let translation = player_transform(&mut app).translation;
+    assert!(translation.y - start.y > 2.0, "only climbed from {start} to {translation}");
+    assert!(translation.x - 0.5 > -1.0 - 0.01, "went into the wall to {translation}");
+}
+
+#[test]
+fn ladder_suspends_gravity() {
+    let mut app = ladder_app();
+    climb(&mut app, 1.0);
+
+    let height = player_transform(&mut app).translation.y;
+    run_ticks(&mut app, &PlayerInput::default(), TICK_RATE as usize / 2);
+
+    assert_eq!(move_mode(&mut app), MoveMode::Ladder);
+    let new_height = player_transform(&mut app).translation.y;
+    assert!((new_height - height).abs() < 0.05, "slid from {height} to {new_height}");
+}
+
+#[test]
+fn looking_down_climbs_down() {
+    let mut app = ladder_app();
+    climb(&mut app, 1.0);
+
+    let player = logical_player(&mut app);
+    app.world_mut().get_mut::<LogicalPlayerController>(player).unwrap().pitch = -1.0;
+    let height = player_transform(&mut app).translation.y;
+    climb(&mut app, 0.25);
+
+    assert_eq!(move_mode(&mut app), MoveMode::Ladder);
+    let new_height = player_transform(&mut app).translation.y;
+    assert!(new_height < height - 0.5, "went from {height} to {new_height}");
+}
+
+#[test]
+fn jumping_pushes_off_the_ladder() {
+    let mut app = ladder_app();
+    climb(&mut app, 1.0);
+
+    run_ticks(&mut app, &PlayerInput { jump: true, ..default() }, 1);
+
+    assert_eq!(move_mode(&mut app), MoveMode::Ground);
+    let props = LogicalPlayerProperties::default();
+    let velocity = player_velocity(&mut app);
+    assert!((velocity.x - props.ladder_jump_off_speed).abs() < 0.1, "pushed off at {velocity}");
+
+    // falls back down instead of grabbing the ladder again
+    run_ticks(&mut app, &PlayerInput::default(), TICK_RATE as usize / 2);
+    assert_eq!(move_mode(&mut app), MoveMode::Ground);
+    assert!(player_velocity(&mut app).y < 0.0);
+}
+
+#[test]
+fn pushing_off_a_ladder_does_not_jump_as_well() {
+    let mut app = ladder_app();
+    climb(&mut app, 1.0);
+
+    // the push off mustn't leave a buffered jump or coyote time for the first ticks in the air
+    run_ticks(&mut app, &PlayerInput { jump: true, ..default() }, 1);
+    let height = player_transform(&mut app).translation.y;
+    run_ticks(&mut app, &PlayerInput::default(), 4);
+
+    let velocity = player_velocity(&mut app);
+    assert!(velocity.y < 0.0, "jumped off at {velocity}");
+    assert!(player_transform(&mut app).translation.y < height);
+}
+
+#[test]
+fn holding_jump_keeps_the_ladder() {
+    let mut app = ladder_app();
+
+    // jumping into the ladder with jump still held
+    let forward_jumping = PlayerInput { movement: Vec3::Z, jump: true, ..default() };
+    run_ticks(&mut app, &forward_jumping, TICK_RATE as usize);
+
+    assert_eq!(move_mode(&mut app), MoveMode::Ladder);
+}
+
+#[test]
+fn volumes_are_read_from_gltf_names_and_extras() {
+    assert_eq!(VolumeKind::from_gltf(Some(&Name::new("Ladder.001")), None), Some(VolumeKind::Ladder));
+    assert_eq!(VolumeKind::from_gltf(Some(&Name::new("ladder")), None), Some(VolumeKind::Ladder));
+    assert_eq!(VolumeKind::from_gltf(Some(&Name::new("Wall")), None), None);
+
+    let extras = GltfExtras { value: r#"{"volume": "ladder"}"#.to_string() };
+    assert_eq!(VolumeKind::from_gltf(Some(&Name::new("Wall")), Some(&extras)), Some(VolumeKind::Ladder));
+}
+
+#[test]
+fn gltf_ladder_colliders_become_sensors() {
+    let mut app = player_app_with(|app| {
+        app.add_systems(Update, tag_volume_colliders);
+    });
+
+    // glTF scenes put the name on the node and the collider on its mesh
+    let ladder_mesh = app.world_mut().spawn(Collider::cuboid(1.0, 1.0, 1.0)).id();
+    app.world_mut().spawn(Name::new("Ladder.001")).add_child(ladder_mesh);
+    let wall_mesh = app.world_mut().spawn(Collider::cuboid(1.0, 1.0, 1.0)).id();
+    app.world_mut().spawn(Name::new("Wall")).add_child(wall_mesh);
+
+    app.update();
+
+    assert!(app.world().get::<Ladder>(ladder_mesh).is_some());
+    assert!(app.world().get::<Sensor>(ladder_mesh).is_some());
+    assert!(app.world().get::<Ladder>(wall_mesh).is_none());
+    assert!(app.world().get::<Sensor>(wall_mesh).is_none());
+}
@@ -0,0 +1,131 @@
+mod common;
+
+use bevy::prelude::*;
+use common::*;
+use my_crate::player::component::{Health, LogicalPlayerProperties, PlayerInput};
+use my_crate::player::demo::{DemoPlayback, DemoRecording};
+use my_crate::player::event::LandedEvent;
+
+fn landing_app() -> App {
+    player_app_with(|app| {
+        app.init_resource::<EventLog<LandedEvent>>()
+        .add_systems(PostUpdate, collect_events::<LandedEvent>);
+    })
+}
+
+fn received(app: &App) -> &[LandedEvent] {
+    &app.world().resource::<EventLog<LandedEvent>>().0
+}
+
+fn health(app: &mut App) -> f32 {
+    let player = logical_player(app);
+    app.world().get::<Health>(player).unwrap().current
+}
+
+/// Drops the player from `height` above the floor onto it, after it settled from the spawn fall.
+fn drop_from(app: &mut App, height: f32) {
+    let props = LogicalPlayerProperties::default();
+    land(app);
+    app.world_mut().resource_mut::<EventLog<LandedEvent>>().0.clear();
+    teleport(app, Vec3::new(0.0, height + props.stand_height / 2.0, 0.0), Vec3::ZERO);
+    run_ticks(app, &PlayerInput::default(), 3 * TICK_RATE as usize);
+}
+
+#[test]
+fn landing_sends_one_event_with_the_impact_speed() {
+    let mut app = landing_app();
+    let props = LogicalPlayerProperties::default();
+    drop_from(&mut app, 3.0);
+
+    let player = logical_player(&mut app);
+    let [landed] = received(&app) else {
+        panic!("expected one landing, got {:?}", received(&app));
+    };
+    assert_eq!(landed.entity, player);
+    assert!(landed.ground_entity.is_some());
+    assert!(landed.normal.angle_between(Vec3::Y) < 0.01, "landed on {}", landed.normal);
+
+    // free fall from 3 above the floor, give or take the ground probe distance
+    let expected = (2.0 * props.gravity * 3.0).sqrt();
+    assert!((landed.speed - expected).abs() < 1.0, "landed at {}, expected {expected}", landed.speed);
+}
+
+#[test]
+fn short_falls_do_no_damage() {
+    let mut app = landing_app();
+    drop_from(&mut app, 3.0);
+
+    assert_eq!(health(&mut app), 100.0);
+}
+
+#[test]
+fn long_falls_hurt_and_very_long_falls_kill() {
+    let props = LogicalPlayerProperties::default();
+
+    let mut app = landing_app();
+    let speed = (props.fall_damage_min_speed + props.fall_damage_max_speed) / 2.0;
+    drop_from(&mut app, speed * speed / (2.0 * props.gravity));
+    let hurt = health(&mut app);
+    assert!(hurt > 20.0 && hurt < 80.0, "health {hurt} after landing at {speed}");
+
+    let mut app = landing_app();
+    drop_from(&mut app, 20.0);
+    let player = logical_player(&mut app);
+    assert!(app.world().get::<Health>(player).unwrap().is_dead());
+}
+
+#[test]
+fn jumping_in_place_lands_softly() {
+    let mut app = landing_app();
+    land(&mut app);
+    app.world_mut().resource_mut::<EventLog<LandedEvent>>().0.clear();
+
+    run_ticks(&mut app, &PlayerInput { jump: true, ..default() }, 1);
+    run_ticks(&mut app, &PlayerInput::default(), TICK_RATE as usize);
+
+    let props = LogicalPlayerProperties::default();
+    let [landed] = received(&app) else {
+        panic!("expected one landing, got {:?}", received(&app));
+    };
+    assert!((landed.speed - props.jump_impulse).abs() < 1.5, "landed at {}", landed.speed);
+    assert_eq!(health(&mut app), 100.0);
+}
+
+#[test]
+fn demo_playback_does_not_hurt() {
+    let mut app = landing_app();
+    let props = LogicalPlayerProperties::default();
+    land(&mut app);
+    teleport(&mut app, Vec3::new(0.0, 20.0 + props.stand_height / 2.0, 0.0), Vec3::ZERO);
+    app.insert_resource(DemoRecording::default());
+    run_ticks(&mut app, &PlayerInput::default(), 3 * TICK_RATE as usize);
+    assert!(health(&mut app) < 100.0, "the recorded fall should hurt");
+    let demo = app.world_mut().remove_resource::<DemoRecording>().unwrap().demo.unwrap();
+
+    let player = logical_player(&mut app);
+    app.world_mut().get_mut::<Health>(player).unwrap().current = 100.0;
+    app.insert_resource(DemoPlayback::new(demo));
+    run_ticks(&mut app, &PlayerInput::default(), 4 * TICK_RATE as usize);
+
+    assert!(app.world().get_resource::<DemoPlayback>().is_none(), "playback did not finish");
+    assert_eq!(health(&mut app), 100.0);
+}
+
+#[test]
+fn fall_damage_follows_the_curve() {
+    let mut props = LogicalPlayerProperties {
+        fall_damage_min_speed: 10.0,
+        fall_damage_max_speed: 20.0,
+        fall_damage_max: 100.0,
+        fall_damage_exponent: 1.0,
+        ..default()
+    };
+    assert_eq!(props.fall_damage(5.0), 0.0);
+    assert_eq!(props.fall_damage(10.0), 0.0);
+    assert!((props.fall_damage(15.0) - 50.0).abs() < 0.001);
+    assert_eq!(props.fall_damage(20.0), 100.0);
+    assert_eq!(props.fall_damage(40.0), 100.0);
+
+    props.fall_damage_exponent = 2.0;
+    assert!((props.fall_damage(15.0) - 25.0).abs() < 0.001);
+}
@@ -0,0 +1,84 @@
+mod common;
+
+use avian3d::prelude::*;
+use bevy::prelude::*;
+use common::*;
+use my_crate::player::component::{LogicalPlayerProperties, MoveMode, PlayerInput};
+use my_crate::player::event::LandedEvent;
+
+/// Puts the player in the air in front of a block whose face is the plane z = -1 and whose top is at `height`.
+fn ledge_app(height: f32) -> App {
+    let mut app = player_app();
+    app.world_mut().spawn((
+        Transform::from_xyz(0.0, height / 2.0, -3.0),
+        RigidBody::Static,
+        Collider::cuboid(4.0, height, 4.0),
+    ));
+    land(&mut app);
+
+    let props = LogicalPlayerProperties::default();
+    teleport(&mut app, Vec3::new(0.0, props.stand_height / 2.0 + 0.5, -0.45), Vec3::ZERO);
+    app
+}
+
+fn forward() -> PlayerInput {
+    // yaw zero looks down -Z
+    PlayerInput { movement: Vec3::Z, ..default() }
+}
+
+#[test]
+fn pressing_into_a_ledge_mantles_onto_it() {
+    let mut app = ledge_app(1.2);
+    run_ticks(&mut app, &forward(), 3);
+
+    assert_eq!(move_mode(&mut app), MoveMode::Mantle);
+    // animated, not teleported
+    let props = LogicalPlayerProperties::default();
+    let feet = player_transform(&mut app).translation.y - props.stand_height / 2.0;
+    assert!(feet < 1.2, "already on the ledge after 3 ticks");
+
+    run_ticks(&mut app, &forward(), TICK_RATE as usize);
+
+    assert_eq!(move_mode(&mut app), MoveMode::Ground);
+    let translation = player_transform(&mut app).translation;
+    let feet = translation.y - props.stand_height / 2.0;
+    assert!(feet > 1.2 - 0.01 && feet < 1.2 + GROUND_TOLERANCE, "standing with feet at {feet}");
+    assert!(translation.z < -1.0, "not over the ledge at {translation}");
+}
+
+#[test]
+fn letting_go_interrupts_the_mantle() {
+    let mut app = ledge_app(1.2);
+    run_ticks(&mut app, &forward(), 3);
+    assert_eq!(move_mode(&mut app), MoveMode::Mantle);
+
+    run_ticks(&mut app, &PlayerInput::default(), 1);
+    assert_eq!(move_mode(&mut app), MoveMode::Ground);
+
+    land(&mut app);
+    let translation = player_transform(&mut app).translation;
+    assert!(translation.z > -1.0, "ended up on the ledge at {translation}");
+}
+
+#[test]
+fn ledges_out_of_reach_are_not_mantled() {
+    let props = LogicalPlayerProperties::default();
+    let mut app = ledge_app(0.5 + props.stand_height + props.mantle_reach + 0.5);
+    run_ticks(&mut app, &forward(), 10);
+
+    assert_ne!(move_mode(&mut app), MoveMode::Mantle);
+}
+
+#[test]
+fn finishing_a_mantle_is_not_a_landing() {
+    let mut app = ledge_app(1.2);
+    app.init_resource::<EventLog<LandedEvent>>()
+    .add_systems(PostUpdate, collect_events::<LandedEvent>);
+
+    run_ticks(&mut app, &forward(), TICK_RATE as usize);
+    assert_eq!(move_mode(&mut app), MoveMode::Ground);
+    run_ticks(&mut app, &PlayerInput::default(), 10);
+
+    let received = &app.world().resource::<EventLog<LandedEvent>>().0;
+    assert!(received.is_empty(), "mantling sent {received:?}");
+}
@@ -0,0 +1,215 @@
+use bevy::math::{Vec3, Vec3Swizzles};
+use my_crate::player::component::{GroundKind, LogicalPlayerProperties};
+use my_crate::player::movement::{clip_velocity, clip_velocity_planes, swim_velocity, GroundHit, MovementInput, MovementState};
+
+const DT: f32 = 1.0 / 64.0;
+
+const FLAT: Option<GroundHit> = Some(GroundHit { normal: Vec3::Y });
+
+fn forward() -> MovementInput {
+    MovementInput { movement: Vec3::Z, ..Default::default() }
+}
+
+fn strafe_right() -> MovementInput {
+    MovementInput { movement: Vec3::X, ..Default::default() }
+}
+
+#[test]
+fn no_friction_on_first_ground_tick() {
+    let props = LogicalPlayerProperties::default();
+    let state = MovementState { velocity: Vec3::new(10.0, 0.0, 0.0), ground_tick: 0 };
+
+    let state = state.step(&MovementInput::default(), &props, FLAT, DT);
+
+    assert_eq!(state.velocity, Vec3::new(10.0, 0.0, 0.0));
+    assert_eq!(state.ground_tick, 1);
+}
+
+#[test]
+fn friction_slows_lateral_speed() {
+    let props = LogicalPlayerProperties::default();
+    let state = MovementState { velocity: Vec3::new(10.0, 0.0, 0.0), ground_tick: 1 };
+
+    let state = state.step(&MovementInput::default(), &props, FLAT, DT);
+
+    // drop = speed * friction * dt = 10 * 10 / 64
+    assert_eq!(state.velocity, Vec3::new(8.4375, 0.0, 0.0));
+}
+
+#[test]
+fn friction_stop_distance() {
+    let props = LogicalPlayerProperties::default();
+    let mut state = MovementState { velocity: Vec3::new(10.0, 0.0, 0.0), ground_tick: 1 };
+    let mut distance = 0.0;
+    let mut ticks = 0;
+
+    while state.velocity != Vec3::ZERO {
+        state = state.step(&MovementInput::default(), &props, FLAT, DT);
+        distance += state.velocity.x * DT;
+        ticks += 1;
+        assert!(ticks < 1000, "friction never stopped the player");
+    }
+
+    assert_eq!(ticks, 20);
+    assert!((distance - 0.80134).abs() < 1e-4, "stopped after {distance}");
+}
+
+#[test]
+fn jump_on_walkable_ground() {
+    let props = LogicalPlayerProperties::default();
+    let input = MovementInput { jump: true, ..Default::default() };
+
+    let state = MovementState { velocity: Vec3::ZERO, ground_tick: 1 }.step(&input, &props, FLAT, DT);
+
+    assert_eq!(state.velocity, Vec3::new(0.0, props.jump_impulse, 0.0));
+}
+
+#[test]
+fn air_acceleration_is_capped() {
+    let props = LogicalPlayerProperties::default();
+    let mut state = MovementState::default().step(&forward(), &props, None, DT);
+
+    // air_acceleration * air_speed_cap * dt = 20 * 2 / 64, gravity * dt = 23 / 64
+    assert_eq!(state.velocity, Vec3::new(0.0, -0.359375, -0.625));
+    assert_eq!(state.ground_tick, 0);
+
+    for _ in 0..10 {
+        state = state.step(&forward(), &props, None, DT);
+    }
+
+    // holding forward can't go past air_speed_cap
+    assert_eq!(state.velocity.z, -props.air_speed_cap);
+}
+
+#[test]
+fn strafing_in_air_gains_speed() {
+    let props = LogicalPlayerProperties::default();
+    let state = MovementState { velocity: Vec3::new(0.0, 0.0, -10.0), ground_tick: 0 };
+
+    let state = state.step(&strafe_right(), &props, None, DT);
+
+    // the wish direction is perpendicular to the velocity, so the full air_speed_cap can be added
+    assert_eq!(state.velocity, Vec3::new(0.625, -0.359375, -10.0));
+    assert!(state.velocity.xz().length() > 10.0);
+}
+
+#[test]
+fn max_air_speed_caps_lateral_speed() {
+    let props = LogicalPlayerProperties::default();
+    let state = MovementState { velocity: Vec3::new(20.0, 0.0, 0.0), ground_tick: 0 };
+
+    let state = state.step(&MovementInput::default(), &props, None, DT);
+
+    assert_eq!(state.velocity, Vec3::new(props.max_air_speed, -0.359375, 0.0));
+}
+
+#[test]
+fn too_steep_slope_slides_downhill() {
+    let props = LogicalPlayerProperties::default();
+    let normal = Vec3::new(40.0_f32.to_radians().sin(), 40.0_f32.to_radians().cos(), 0.0);
+    assert_eq!(props.classify_slope(normal), GroundKind::TooSteep);
+
+    let state = MovementState::default().step(&MovementInput::default(), &props, Some(GroundHit { normal }), DT);
+
+    assert!(state.velocity.x > 0.0);
+    assert!(state.velocity.y < 0.0);
+    assert!(Vec3::dot(state.velocity, normal).abs() < 1e-6);
+    assert_eq!(state.ground_tick, 0);
+}
+
+#[test]
+fn surfing_a_45_degree_ramp_keeps_speed() {
+    let props = LogicalPlayerProperties::default();
+    let normal = Vec3::new(1.0, 1.0, 0.0).normalize();
+    assert_eq!(props.classify_slope(normal), GroundKind::Surfable);
+    let ramp = Some(GroundHit { normal });
+
+    let mut state = MovementState { velocity: Vec3::new(0.0, 0.0, -10.0), ground_tick: 0 };
+    for _ in 0..32 {
+        let speed = state.velocity.length();
+        state = state.step(&MovementInput::default(), &props, ramp, DT);
+
+        // the speed along the ramp is untouched, gravity only adds speed down the slope
+        assert_eq!(state.velocity.z, -10.0);
+        assert!(state.velocity.length() >= speed, "slowed from {speed} to {}", state.velocity.length());
+        assert!(Vec3::dot(state.velocity, normal).abs() < 1e-5);
+    }
+    assert!(state.velocity.x > 0.0);
+}
+
+#[test]
+fn overbounce_pushes_off_the_plane() {
+    assert_eq!(clip_velocity(Vec3::new(3.0, -1.0, 0.0), Vec3::Y, 1.0), Vec3::new(3.0, 0.0, 0.0));
+    assert_eq!(clip_velocity(Vec3::new(3.0, -1.0, 0.0), Vec3::Y, 1.5), Vec3::new(3.0, 0.5, 0.0));
+}
+
+#[test]
+fn clipping_between_two_ramps_follows_the_crease() {
+    let left = Vec3::new(1.0, 1.0, 0.0).normalize();
+    let right = Vec3::new(-1.0, 1.0, 0.0).normalize();
+
+    let velocity = clip_velocity_planes(Vec3::new(0.0, -1.0, -1.0), &[left, right], 1.0);
+    assert!((velocity - Vec3::new(0.0, 0.0, -1.0)).length() < 1e-6, "got {velocity}");
+
+    // moving away from a plane leaves the velocity alone
+    assert_eq!(clip_velocity_planes(Vec3::new(1.0, 1.0, 0.0), &[left], 1.0), Vec3::new(1.0, 1.0, 0.0));
+}
+
+#[test]
+fn water_drag_stops_a_floating_swimmer() {
+    let props = LogicalPlayerProperties::default();
+    // buoyancy and gravity cancel out at this depth
+    let immersion = props.gravity / props.buoyancy;
+
+    let mut velocity = Vec3::new(5.0, 0.0, 0.0);
+    for _ in 0..64 {
+        velocity = swim_velocity(velocity, &MovementInput::default(), &props, immersion, DT);
+    }
+
+    assert!(velocity.length() < 0.5, "still moving at {velocity}");
+}
+
+#[test]
+fn swimming_follows_the_view_pitch() {
+    let props = LogicalPlayerProperties::default();
+    let immersion = props.gravity / props.buoyancy;
+    let input = MovementInput { pitch: -0.5, ..forward() };
+
+    let mut velocity = Vec3::ZERO;
+    for _ in 0..64 {
+        velocity = swim_velocity(velocity, &input, &props, immersion, DT);
+    }
+
+    assert!(velocity.y < -1.0 && velocity.z < -1.0, "swimming at {velocity}");
+    assert!(velocity.length() <= props.swim_speed + 0.01);
+}
+
+#[test]
+fn sliding_speeds_up_downhill() {
+    let props = LogicalPlayerProperties::default();
+    let slope = 20.0_f32.to_radians();
+    // walkable slope going down towards +X
+    let ground = Some(GroundHit { normal: Vec3::new(slope.sin(), slope.cos(), 0.0) });
+    let input = MovementInput { sliding: true, crouched: true, ..Default::default() };
+
+    let mut state = MovementState { velocity: Vec3::new(5.0, 0.0, 0.0), ground_tick: 1 };
+    for _ in 0..64 {
+        state = state.step(&input, &props, ground, DT);
+    }
+
+    assert!(state.velocity.x > 5.0, "slid at {}", state.velocity);
+}
+
+#[test]
+fn sliding_keeps_more_speed_than_walking() {
+    let props = LogicalPlayerProperties::default();
+    let start = MovementState { velocity: Vec3::new(10.0, 0.0, 0.0), ground_tick: 1 };
+
+    let sliding = MovementInput { sliding: true, crouched: true, ..Default::default() };
+    let crouching = MovementInput { crouched: true, ..Default::default() };
+    let slid = start.step(&sliding, &props, FLAT, DT);
+    let walked = start.step(&crouching, &props, FLAT, DT);
+
+    assert!(slid.velocity.x > walked.velocity.x);
+    assert!(slid.velocity.x < 10.0);
+}
@@ -0,0 +1,97 @@
+mod common;
+
+use avian3d::prelude::*;
+use bevy::prelude::*;
+use common::*;
+use my_crate::environment::platform::{move_platforms, MovingPlatform};
+use my_crate::player::component::{GroundState, LogicalPlayerController, PlayerInput};
+
+/// Lands the player on a wide kinematic platform under the spawn point and returns the platform.
+fn platform_app() -> (App, Entity) {
+    let mut app = player_app();
+    let platform = app.world_mut().spawn((
+        Transform::from_xyz(0.0, 1.0, 0.0),
+        RigidBody::Kinematic,
+        Collider::cuboid(10.0, 0.5, 10.0),
+    )).id();
+    land(&mut app);
+    (app, platform)
+}
+
+fn platform_translation(app: &App, platform: Entity) -> Vec3 {
+    app.world().get::<Position>(platform).unwrap().0
+}
+
+#[test]
+fn player_rides_a_moving_platform() {
+    let (mut app, platform) = platform_app();
+    let player = logical_player(&mut app);
+    assert_eq!(app.world().get::<GroundState>(player).unwrap().entity, Some(platform));
+
+    app.world_mut().get_mut::<LinearVelocity>(platform).unwrap().0 = Vec3::new(3.0, 0.0, 0.0);
+    let offset = player_transform(&mut app).translation - platform_translation(&app, platform);
+    run_ticks(&mut app, &PlayerInput::default(), TICK_RATE as usize);
+
+    let moved = player_transform(&mut app).translation - platform_translation(&app, platform);
+    assert!(platform_translation(&app, platform).x > 2.9, "platform didn't move");
+    assert!((moved - offset).xz().length() < 0.1, "slid {} over the platform", moved - offset);
+}
+
+#[test]
+fn player_rides_an_elevator() {
+    let (mut app, platform) = platform_app();
+    app.world_mut().get_mut::<LinearVelocity>(platform).unwrap().0 = Vec3::new(0.0, 2.0, 0.0);
+
+    let height = player_transform(&mut app).translation.y - platform_translation(&app, platform).y;
+    run_ticks(&mut app, &PlayerInput::default(), TICK_RATE as usize);
+
+    let new_height = player_transform(&mut app).translation.y - platform_translation(&app, platform).y;
+    assert!((new_height - height).abs() < GROUND_TOLERANCE, "height over the elevator went from {height} to {new_height}");
+}
+
+#[test]
+fn jumping_off_a_platform_keeps_its_momentum() {
+    let (mut app, platform) = platform_app();
+    app.world_mut().get_mut::<LinearVelocity>(platform).unwrap().0 = Vec3::new(3.0, 0.0, 0.0);
+    run_ticks(&mut app, &PlayerInput::default(), TICK_RATE as usize / 2);
+
+    run_ticks(&mut app, &PlayerInput { jump: true, ..default() }, 1);
+    run_ticks(&mut app, &PlayerInput::default(), 10);
+
+    let player = logical_player(&mut app);
+    assert!(!app.world().get::<GroundState>(player).unwrap().is_grounded());
+    let velocity = player_velocity(&mut app);
+    assert!((velocity.x - 3.0).abs() < 0.1, "flying at {velocity}");
+}
+
+#[test]
+fn spinning_platform_turns_the_view() {
+    let (mut app, platform) = platform_app();
+    app.world_mut().get_mut::<AngularVelocity>(platform).unwrap().0 = Vec3::new(0.0, 1.0, 0.0);
+
+    let player = logical_player(&mut app);
+    let yaw = app.world().get::<LogicalPlayerController>(player).unwrap().yaw;
+    run_ticks(&mut app, &PlayerInput::default(), TICK_RATE as usize);
+
+    let turned = app.world().get::<LogicalPlayerController>(player).unwrap().yaw - yaw;
+    assert!((turned - 1.0).abs() < 0.05, "turned by {turned}");
+}
+
+#[test]
+fn moving_platform_follows_its_path() {
+    let mut app = player_app_with(|app| {
+        app.add_systems(FixedUpdate, move_platforms);
+    });
+    let start = Vec3::new(20.0, 1.0, 0.0);
+    let platform = app.world_mut().spawn((
+        Transform::from_translation(start),
+        RigidBody::Kinematic,
+        Collider::cuboid(2.0, 0.5, 2.0),
+        MovingPlatform { start, end: start + Vec3::X * 4.0, period: 2.0, spin: 0.0, time: 0.0 },
+    )).id();
+
+    // half a round trip reaches the end
+    run_ticks(&mut app, &PlayerInput::default(), TICK_RATE as usize);
+    let translation = platform_translation(&app, platform);
+    assert!((translation - (start + Vec3::X * 4.0)).length() < 0.1, "platform at {translation}");
+}
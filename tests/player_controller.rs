@@ -0,0 +1,76 @@
+mod common;
+
+use bevy::prelude::*;
+use common::*;
+use my_crate::player::component::{GroundKind, GroundState, LogicalPlayerProperties, PlayerInput};
+
+#[test]
+fn player_lands_on_floor() {
+    let mut app = player_app();
+    land(&mut app);
+
+    let props = LogicalPlayerProperties::default();
+    let transform = player_transform(&mut app);
+    let velocity = player_velocity(&mut app);
+
+    let feet = transform.translation.y - props.stand_height / 2.0;
+    assert!(feet > -0.05 && feet < GROUND_TOLERANCE, "resting at {}", transform.translation);
+    assert!(velocity.length() < 0.05, "still moving at {velocity}");
+
+    let player = logical_player(&mut app);
+    assert_eq!(app.world().get::<GroundState>(player).unwrap().kind, GroundKind::Walkable);
+}
+
+#[test]
+fn walking_forward_reaches_walk_speed() {
+    let mut app = player_app();
+    land(&mut app);
+    let start = player_transform(&mut app).translation;
+
+    let forward = PlayerInput { movement: Vec3::Z, ..default() };
+    run_ticks(&mut app, &forward, TICK_RATE as usize);
+
+    let props = LogicalPlayerProperties::default();
+    let velocity = player_velocity(&mut app);
+    let translation = player_transform(&mut app).translation;
+
+    // yaw starts at zero, so forward is -Z
+    assert!((velocity.xz().length() - props.walk_speed).abs() < 0.05, "walking at {velocity}");
+    assert!(velocity.z < 0.0);
+    assert!(translation.z < start.z - props.walk_speed / 2.0, "only moved to {translation}");
+    assert!(translation.x.abs() < 0.01);
+}
+
+#[test]
+fn jumping_leaves_the_ground() {
+    let mut app = player_app();
+    land(&mut app);
+    let rest_height = player_transform(&mut app).translation.y;
+
+    let jump = PlayerInput { jump: true, ..default() };
+    run_ticks(&mut app, &jump, 1);
+    run_ticks(&mut app, &PlayerInput::default(), 10);
+
+    assert!(player_transform(&mut app).translation.y > rest_height + 0.5);
+
+    // and comes back down
+    land(&mut app);
+    assert!((player_transform(&mut app).translation.y - rest_height).abs() < GROUND_TOLERANCE);
+}
+
+#[test]
+fn crouching_lowers_the_hull_on_the_ground() {
+    let mut app = player_app();
+    land(&mut app);
+    let rest_height = player_transform(&mut app).translation.y;
+
+    let crouch = PlayerInput { crouch: true, ..default() };
+    run_ticks(&mut app, &crouch, 10);
+
+    let props = LogicalPlayerProperties::default();
+    let height_change = props.stand_height - props.crouch_height;
+    assert!((player_transform(&mut app).translation.y - (rest_height - height_change / 2.0)).abs() < 0.05);
+
+    run_ticks(&mut app, &PlayerInput::default(), 10);
+    assert!((player_transform(&mut app).translation.y - rest_height).abs() < 0.05);
+}
@@ -0,0 +1,73 @@
+mod common;
+
+use std::{fs, path::Path, thread, time::Duration};
+
+use avian3d::prelude::*;
+use bevy::prelude::*;
+use common::{land, logical_player, player_app};
+use my_crate::player::component::LogicalPlayerProperties;
+use my_crate::player::preset::{MovementPreset, MovementPresetHandle, BUILTIN_PRESETS};
+
+fn read_preset(name: &str) -> MovementPreset {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("assets").join(MovementPreset::path(name));
+    let ron = fs::read_to_string(&path).unwrap_or_else(|error| panic!("{}: {error}", path.display()));
+    ron::from_str(&ron).unwrap_or_else(|error| panic!("{}: {error}", path.display()))
+}
+
+#[test]
+fn builtin_presets_parse() {
+    for name in BUILTIN_PRESETS {
+        read_preset(name);
+    }
+}
+
+#[test]
+fn source_preset_matches_defaults() {
+    assert_eq!(read_preset("source").properties, LogicalPlayerProperties::default());
+}
+
+#[test]
+fn preset_angles_are_in_degrees() {
+    let realistic = read_preset("realistic").properties;
+    assert!((realistic.max_slope_angle - 40.0_f32.to_radians()).abs() < 1e-6);
+}
+
+#[test]
+fn loaded_preset_replaces_properties_and_hull() {
+    let mut app = player_app();
+    land(&mut app);
+    let player = logical_player(&mut app);
+    let feet = app.world().get::<Transform>(player).unwrap().translation.y - 0.5 * LogicalPlayerProperties::default().stand_height;
+
+    let handle = app.world().resource::<AssetServer>().load(MovementPreset::path("realistic"));
+    app.world_mut().entity_mut(player).insert(MovementPresetHandle(handle));
+
+    let expected = read_preset("realistic").properties;
+    for _ in 0..500 {
+        if *app.world().get::<LogicalPlayerProperties>(player).unwrap() == expected {
+            break;
+        }
+        // loading happens on the IO task pool
+        thread::sleep(Duration::from_millis(2));
+        app.update();
+    }
+    assert_eq!(*app.world().get::<LogicalPlayerProperties>(player).unwrap(), expected);
+
+    let collider = app.world().get::<Collider>(player).unwrap();
+    let cylinder = collider.shape().as_cylinder().unwrap();
+    assert_eq!(cylinder.half_height * 2.0, expected.stand_height);
+
+    // the taller hull grows upwards from the feet
+    let new_feet = app.world().get::<Transform>(player).unwrap().translation.y - 0.5 * expected.stand_height;
+    assert!((new_feet - feet).abs() < 0.05, "feet moved from {feet} to {new_feet}");
+}
+
+#[test]
+fn saved_preset_round_trips() {
+    let preset = read_preset("quake");
+
+    let ron = preset.to_ron_string().unwrap();
+    let reloaded: MovementPreset = ron::from_str(&ron).unwrap();
+
+    assert_eq!(reloaded.properties, preset.properties);
+}
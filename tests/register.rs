@@ -0,0 +1,39 @@
+use std::any::TypeId;
+
+use bevy::prelude::*;
+use bevy_inspector_egui::prelude::ReflectInspectorOptions;
+use my_crate::player::component::{GroundState, Health, LogicalPlayer, LogicalPlayerController, LogicalPlayerProperties, PlayerControls, PlayerInput, PreviousTranslation, RenderPlayer, Stamina};
+use my_crate::player::preset::MovementPresetHandle;
+use my_crate::register::RegisterPlugin;
+use my_crate::weapon::component::Weapon;
+
+#[test]
+fn player_components_are_registered_for_the_inspector() {
+    let mut app = App::new();
+    app.add_plugins(RegisterPlugin);
+
+    let type_registry = app.world().resource::<AppTypeRegistry>().read();
+    let components = [
+        TypeId::of::<LogicalPlayer>(),
+        TypeId::of::<LogicalPlayerProperties>(),
+        TypeId::of::<LogicalPlayerController>(),
+        TypeId::of::<PlayerControls>(),
+        TypeId::of::<PlayerInput>(),
+        TypeId::of::<Stamina>(),
+        TypeId::of::<Health>(),
+        TypeId::of::<GroundState>(),
+        TypeId::of::<PreviousTranslation>(),
+        TypeId::of::<RenderPlayer>(),
+        TypeId::of::<MovementPresetHandle>(),
+        TypeId::of::<Weapon>(),
+    ];
+    for type_id in components {
+        let registration = type_registry.get(type_id).expect("component is not registered");
+        assert!(registration.data::<ReflectComponent>().is_some(), "{} can't be edited as a component", registration.type_info().type_path());
+    }
+
+    // tuning values carry their inspector ranges
+    for type_id in [TypeId::of::<LogicalPlayerProperties>(), TypeId::of::<PlayerControls>(), TypeId::of::<Stamina>(), TypeId::of::<Weapon>()] {
+        assert!(type_registry.get_type_data::<ReflectInspectorOptions>(type_id).is_some());
+    }
+}
@@ -0,0 +1,107 @@
+mod common;
+
+use bevy::prelude::*;
+use common::*;
+use my_crate::player::component::{LogicalPlayerController, LogicalPlayerProperties, PlayerInput};
+use my_crate::player::event::SlideEvent;
+
+/// Lands the player and sprints forward until it is at full sprint speed.
+fn sprinting_app() -> App {
+    let mut app = player_app_with(|app| {
+        app.init_resource::<EventLog<SlideEvent>>()
+        .add_systems(PostUpdate, collect_events::<SlideEvent>);
+    });
+    land(&mut app);
+    run_ticks(&mut app, &sprint(), TICK_RATE as usize);
+    app
+}
+
+fn sprint() -> PlayerInput {
+    PlayerInput { movement: Vec3::Z, sprint: true, ..default() }
+}
+
+fn slide() -> PlayerInput {
+    PlayerInput { crouch: true, ..sprint() }
+}
+
+fn controller(app: &mut App) -> &LogicalPlayerController {
+    let player = logical_player(app);
+    app.world().get::<LogicalPlayerController>(player).unwrap()
+}
+
+fn received(app: &App) -> &[SlideEvent] {
+    &app.world().resource::<EventLog<SlideEvent>>().0
+}
+
+#[test]
+fn crouching_while_sprinting_slides() {
+    let mut app = sprinting_app();
+    run_ticks(&mut app, &slide(), 1);
+
+    let player = logical_player(&mut app);
+    assert!(controller(&mut app).sliding);
+    assert!(controller(&mut app).crouched);
+    assert_eq!(received(&app), &[SlideEvent::Started(player)]);
+
+    run_ticks(&mut app, &slide(), TICK_RATE as usize / 2);
+
+    // walking crouched would have slowed down to crouch_speed by now
+    let props = LogicalPlayerProperties::default();
+    let speed = player_velocity(&mut app).xz().length();
+    assert!(speed > props.crouch_speed + 2.0, "slid at {speed}");
+}
+
+#[test]
+fn slide_ends_below_the_minimum_speed() {
+    let mut app = sprinting_app();
+    let player = logical_player(&mut app);
+
+    let mut ticks = 0;
+    run_ticks(&mut app, &slide(), 1);
+    while controller(&mut app).sliding && ticks < 5 * TICK_RATE as usize {
+        run_ticks(&mut app, &slide(), 1);
+        ticks += 1;
+    }
+
+    assert!(!controller(&mut app).sliding, "still sliding after {ticks} ticks");
+    assert!(controller(&mut app).slide_cooldown > 0.0);
+    assert_eq!(received(&app), &[SlideEvent::Started(player), SlideEvent::Stopped(player)]);
+
+    let props = LogicalPlayerProperties::default();
+    assert!(player_velocity(&mut app).xz().length() < props.slide_min_speed);
+}
+
+#[test]
+fn standing_up_ends_the_slide() {
+    let mut app = sprinting_app();
+    run_ticks(&mut app, &slide(), TICK_RATE as usize / 4);
+    run_ticks(&mut app, &sprint(), 1);
+
+    assert!(!controller(&mut app).sliding);
+    assert!(!controller(&mut app).crouched);
+}
+
+#[test]
+fn slide_cooldown_blocks_the_next_slide() {
+    let mut app = sprinting_app();
+    run_ticks(&mut app, &slide(), TICK_RATE as usize / 4);
+    run_ticks(&mut app, &sprint(), TICK_RATE as usize / 4);
+
+    run_ticks(&mut app, &slide(), 1);
+    assert!(!controller(&mut app).sliding);
+    assert!(controller(&mut app).crouched);
+}
+
+#[test]
+fn slide_jump_keeps_momentum() {
+    let mut app = sprinting_app();
+    run_ticks(&mut app, &slide(), TICK_RATE as usize / 4);
+    let speed = player_velocity(&mut app).xz().length();
+
+    run_ticks(&mut app, &PlayerInput { jump: true, ..slide() }, 1);
+    run_ticks(&mut app, &slide(), TICK_RATE as usize / 4);
+
+    assert!(controller(&mut app).sliding);
+    let new_speed = player_velocity(&mut app).xz().length();
+    assert!(new_speed > speed - 0.5, "slowed from {speed} to {new_speed}");
+}
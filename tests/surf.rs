@@ -0,0 +1,51 @@
+mod common;
+
+use std::f32::consts::FRAC_PI_4;
+
+use avian3d::prelude::*;
+use bevy::prelude::*;
+use common::*;
+use my_crate::player::component::{GroundKind, GroundState, LogicalPlayerProperties, PlayerInput};
+
+// Top of the 45° ramp face that faces +X, the face is the plane x + y = RAMP_TOP for 0 <= x <= RAMP_TOP - RAMP_Y
+const RAMP_Y: f32 = 5.0;
+const RAMP_TOP: f32 = RAMP_Y + 2.0 * std::f32::consts::SQRT_2;
+
+/// A 4x4 box turned 45° around Z into a long diamond high above the floor.
+fn spawn_ramp(app: &mut App) {
+    app.world_mut().spawn((
+        Transform::from_xyz(0.0, RAMP_Y, 0.0).with_rotation(Quat::from_rotation_z(-FRAC_PI_4)),
+        RigidBody::Static,
+        Collider::cuboid(4.0, 4.0, 200.0),
+    ));
+}
+
+#[test]
+fn surfing_a_45_degree_ramp_keeps_speed() {
+    let mut app = player_app();
+    spawn_ramp(&mut app);
+
+    // the lowest point of the upright cylinder towards the ramp is the rim of its bottom cap
+    let props = LogicalPlayerProperties::default();
+    let x = 1.2;
+    let y = RAMP_TOP + 0.5 + props.stand_height / 2.0 - x + 0.03;
+    teleport(&mut app, Vec3::new(x, y, 0.0), Vec3::new(0.0, 0.0, -10.0));
+
+    for _ in 0..20 {
+        run_ticks(&mut app, &PlayerInput::default(), 1);
+        let velocity = player_velocity(&mut app);
+
+        assert!((velocity.z + 10.0).abs() < 0.05, "lost speed along the ramp: {velocity}");
+        assert!(velocity.length() >= 10.0 - 0.05, "slowed down to {velocity}");
+    }
+
+    let player = logical_player(&mut app);
+    assert_eq!(app.world().get::<GroundState>(player).unwrap().kind, GroundKind::Surfable);
+
+    // slid down the ramp and along it without sinking into it
+    let translation = player_transform(&mut app).translation;
+    assert!(translation.x > x);
+    assert!(translation.z < -2.5);
+    let feet_gap = translation.x - 0.5 + translation.y - props.stand_height / 2.0 - RAMP_TOP;
+    assert!(feet_gap > -0.05, "sank {feet_gap} into the ramp");
+}
@@ -0,0 +1,78 @@
+mod common;
+
+use avian3d::prelude::*;
+use bevy::prelude::*;
+use common::*;
+use my_crate::environment::volume::{volume_layers, Water};
+use my_crate::player::component::{MoveMode, PlayerInput, WaterState};
+
+// Top of the pool spawned by water_app
+const WATERLINE: f32 = 4.0;
+
+/// Fills the area around the spawn point with water from the floor up to `WATERLINE`.
+fn water_app() -> App {
+    let mut app = player_app();
+    app.world_mut().spawn((
+        Transform::from_xyz(0.0, WATERLINE / 2.0, 0.0),
+        Collider::cuboid(40.0, WATERLINE, 40.0),
+        Sensor,
+        volume_layers(),
+        Water,
+    ));
+    app
+}
+
+fn water_state(app: &mut App) -> (f32, bool) {
+    let player = logical_player(app);
+    let water_state = app.world().get::<WaterState>(player).unwrap();
+    (water_state.immersion, water_state.underwater)
+}
+
+#[test]
+fn player_floats_to_the_surface() {
+    let mut app = water_app();
+    // standing on the bottom of the pool
+    teleport(&mut app, Vec3::new(0.0, 1.0, 0.0), Vec3::ZERO);
+    run_ticks(&mut app, &PlayerInput::default(), 1);
+
+    assert_eq!(move_mode(&mut app), MoveMode::Swim);
+    assert!(water_state(&mut app).1, "not underwater at the bottom");
+
+    run_ticks(&mut app, &PlayerInput::default(), 5 * TICK_RATE as usize);
+
+    assert_eq!(move_mode(&mut app), MoveMode::Swim);
+    let (immersion, underwater) = water_state(&mut app);
+    assert!(!underwater, "still underwater at {}", player_transform(&mut app).translation);
+    assert!(immersion > 0.4 && immersion < 0.8, "floating at immersion {immersion}");
+    assert!(player_velocity(&mut app).y.abs() < 0.5, "still bobbing at {}", player_velocity(&mut app));
+}
+
+#[test]
+fn jumping_at_the_waterline_leaves_the_water() {
+    let mut app = water_app();
+    run_ticks(&mut app, &PlayerInput::default(), 5 * TICK_RATE as usize);
+    assert_eq!(move_mode(&mut app), MoveMode::Swim);
+
+    run_ticks(&mut app, &PlayerInput { jump: true, ..default() }, 1);
+
+    assert_eq!(move_mode(&mut app), MoveMode::Ground);
+    assert!(player_velocity(&mut app).y > 0.0);
+}
+
+#[test]
+fn shallow_water_is_waded_through() {
+    let mut app = player_app();
+    app.world_mut().spawn((
+        Transform::from_xyz(0.0, 0.25, 0.0),
+        Collider::cuboid(40.0, 0.5, 40.0),
+        Sensor,
+        volume_layers(),
+        Water,
+    ));
+    land(&mut app);
+
+    assert_eq!(move_mode(&mut app), MoveMode::Ground);
+    let (immersion, underwater) = water_state(&mut app);
+    assert!(immersion > 0.0 && immersion < 0.4, "immersion {immersion}");
+    assert!(!underwater);
+}
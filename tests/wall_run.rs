@@ -0,0 +1,71 @@
+mod common;
+
+use avian3d::prelude::*;
+use bevy::prelude::*;
+use common::*;
+use my_crate::player::component::{LogicalPlayerProperties, MoveMode, PlayerInput};
+
+/// Puts the player in the air next to a tall wall whose face is the plane x = -1, running along it down -Z.
+fn wall_run_app(speed: f32) -> App {
+    let mut app = player_app();
+    app.world_mut().spawn((
+        Transform::from_xyz(-1.5, 10.0, 0.0),
+        RigidBody::Static,
+        Collider::cuboid(1.0, 20.0, 200.0),
+    ));
+    land(&mut app);
+
+    teleport(&mut app, Vec3::new(-0.45, 8.0, 0.0), Vec3::new(0.0, 0.0, -speed));
+    app
+}
+
+fn run_forward(app: &mut App, ticks: usize) {
+    // yaw zero looks down -Z
+    run_ticks(app, &PlayerInput { movement: Vec3::Z, ..default() }, ticks);
+}
+
+#[test]
+fn fast_airborne_player_runs_along_the_wall() {
+    let mut app = wall_run_app(10.0);
+    run_forward(&mut app, TICK_RATE as usize / 2);
+
+    assert_eq!(move_mode(&mut app), MoveMode::WallRun);
+    let translation = player_transform(&mut app).translation;
+    // free fall would have dropped almost 3 by now
+    assert!(translation.y > 8.0 - 1.0, "fell to {translation}");
+    assert!(translation.z < -3.0, "stuck at {translation}");
+    assert!(translation.x - 0.5 > -1.0 - 0.01, "went into the wall to {translation}");
+}
+
+#[test]
+fn slow_player_does_not_run_along_the_wall() {
+    let mut app = wall_run_app(3.0);
+    run_forward(&mut app, TICK_RATE as usize / 4);
+
+    assert_eq!(move_mode(&mut app), MoveMode::Ground);
+}
+
+#[test]
+fn wall_run_ends_after_the_time_limit() {
+    let mut app = wall_run_app(10.0);
+    let props = LogicalPlayerProperties::default();
+    run_forward(&mut app, ((props.wall_run_max_time + 0.25) * TICK_RATE as f32) as usize);
+
+    // the same wall can't be run along again before landing
+    assert_eq!(move_mode(&mut app), MoveMode::Ground);
+}
+
+#[test]
+fn wall_jump_kicks_off_along_the_normal() {
+    let mut app = wall_run_app(10.0);
+    run_forward(&mut app, TICK_RATE as usize / 4);
+    assert_eq!(move_mode(&mut app), MoveMode::WallRun);
+
+    run_ticks(&mut app, &PlayerInput { movement: Vec3::Z, jump: true, ..default() }, 1);
+
+    assert_eq!(move_mode(&mut app), MoveMode::Ground);
+    let props = LogicalPlayerProperties::default();
+    let velocity = player_velocity(&mut app);
+    assert!((velocity.x - props.wall_jump_speed).abs() < 0.5, "kicked off at {velocity}");
+    assert!(velocity.y > 0.0 && velocity.z < -5.0, "kicked off at {velocity}");
+}
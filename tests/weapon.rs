@@ -0,0 +1,169 @@
+mod common;
+
+use avian3d::prelude::*;
+use bevy::prelude::*;
+use common::*;
+use my_crate::player::component::{LogicalPlayerController, LogicalPlayerProperties, PlayerInput, RenderPlayer};
+use my_crate::player::demo::{DemoPlayback, DemoRecording};
+use my_crate::weapon::component::Weapon;
+use my_crate::weapon::event::HitEvent;
+use my_crate::weapon::WeaponPlugin;
+
+/// Lands the player holding `weapon`, looking down -Z at a wall whose near face is `distance` away.
+/// Headless apps have no camera, so the weapon sits on a bare RenderPlayer.
+fn weapon_app(weapon: Weapon, distance: f32) -> (App, Entity) {
+    let mut app = player_app_with(|app| {
+        app.add_plugins(WeaponPlugin)
+        .init_resource::<EventLog<HitEvent>>()
+        .add_systems(PostUpdate, collect_events::<HitEvent>);
+    });
+    let wall = app.world_mut().spawn((
+        Transform::from_xyz(0.0, 2.0, -distance - 0.5),
+        RigidBody::Static,
+        Collider::cuboid(20.0, 4.0, 1.0),
+    )).id();
+
+    let player = logical_player(&mut app);
+    let props = LogicalPlayerProperties::default();
+    app.world_mut().spawn((
+        RenderPlayer { logical_entity: player, view_offset: 0.0, hull_height: props.stand_height },
+        weapon,
+    ));
+    land(&mut app);
+    (app, wall)
+}
+
+fn fire() -> PlayerInput {
+    PlayerInput { fire: true, ..default() }
+}
+
+fn weapon(app: &mut App) -> Weapon {
+    app.world_mut().query::<&Weapon>().single(app.world()).clone()
+}
+
+fn hits(app: &App) -> &[HitEvent] {
+    &app.world().resource::<EventLog<HitEvent>>().0
+}
+
+#[test]
+fn firing_hits_the_wall_in_view() {
+    let (mut app, wall) = weapon_app(Weapon::rifle(), 10.0);
+    run_ticks(&mut app, &fire(), 1);
+
+    let player = logical_player(&mut app);
+    let [hit] = hits(&app) else {
+        panic!("expected one hit, got {:?}", hits(&app));
+    };
+    assert_eq!(hit.shooter, player);
+    assert_eq!(hit.entity, wall);
+    assert!((hit.point.z + 10.0).abs() < 0.01, "hit at {}", hit.point);
+    assert!((hit.distance - 10.0).abs() < 0.01);
+    assert!(hit.normal.angle_between(Vec3::Z) < 0.01, "hit normal {}", hit.normal);
+    assert_eq!(hit.damage, Weapon::rifle().damage);
+    assert_eq!(weapon(&mut app).ammo, Weapon::rifle().magazine_size - 1);
+}
+
+#[test]
+fn shots_follow_the_view() {
+    let (mut app, _) = weapon_app(Weapon::rifle(), 10.0);
+    let player = logical_player(&mut app);
+    app.world_mut().get_mut::<LogicalPlayerController>(player).unwrap().yaw = std::f32::consts::PI;
+
+    run_ticks(&mut app, &fire(), 1);
+
+    assert!(hits(&app).is_empty(), "hit {:?} looking away", hits(&app));
+    assert_eq!(weapon(&mut app).ammo, Weapon::rifle().magazine_size - 1);
+}
+
+#[test]
+fn damage_falls_off_with_distance() {
+    let rifle = Weapon::rifle();
+    let (mut app, _) = weapon_app(rifle.clone(), rifle.falloff_end + 10.0);
+    run_ticks(&mut app, &fire(), 1);
+
+    let [hit] = hits(&app) else {
+        panic!("expected one hit, got {:?}", hits(&app));
+    };
+    assert!((hit.damage - rifle.damage * rifle.falloff_min_factor).abs() < 0.001, "damage {}", hit.damage);
+
+    let halfway = (rifle.falloff_start + rifle.falloff_end) / 2.0;
+    let expected = rifle.damage * (1.0 + rifle.falloff_min_factor) / 2.0;
+    assert!((rifle.damage_at(halfway) - expected).abs() < 0.001);
+}
+
+#[test]
+fn automatic_weapons_fire_at_their_fire_rate() {
+    let (mut app, _) = weapon_app(Weapon::rifle(), 10.0);
+    run_ticks(&mut app, &fire(), TICK_RATE as usize);
+
+    // shots at 0, 0.1, ... 0.9 seconds
+    assert_eq!(hits(&app).len(), 10);
+    assert_eq!(weapon(&mut app).ammo, Weapon::rifle().magazine_size - 10);
+}
+
+#[test]
+fn shotgun_pellets_spread_over_the_cone() {
+    let shotgun = Weapon::shotgun();
+    let (mut app, _) = weapon_app(shotgun.clone(), 10.0);
+    // semi-automatic, holding the trigger fires once
+    run_ticks(&mut app, &fire(), TICK_RATE as usize);
+
+    assert_eq!(hits(&app).len(), shotgun.pellets as usize);
+    assert_eq!(weapon(&mut app).ammo, shotgun.magazine_size - 1);
+
+    let center = hits(&app)[0].point;
+    let max_offset = 10.0 * shotgun.spread.tan() + 0.01;
+    for (index, hit) in hits(&app).iter().enumerate() {
+        let offset = (hit.point - center).length();
+        assert!(offset <= max_offset, "pellet {index} landed {offset} off center");
+        for other in &hits(&app)[index + 1..] {
+            assert!((hit.point - other.point).length() > 0.05, "pellets overlap at {}", hit.point);
+        }
+    }
+}
+
+#[test]
+fn firing_an_empty_magazine_reloads_it() {
+    let (mut app, _) = weapon_app(Weapon { ammo: 1, automatic: false, ..Weapon::rifle() }, 10.0);
+    run_ticks(&mut app, &fire(), 1);
+    run_ticks(&mut app, &PlayerInput::default(), TICK_RATE as usize / 4);
+    run_ticks(&mut app, &fire(), 1);
+
+    assert_eq!(hits(&app).len(), 1);
+    assert!(weapon(&mut app).is_reloading());
+
+    let reload_ticks = (Weapon::rifle().reload_time * TICK_RATE as f32) as usize + 1;
+    run_ticks(&mut app, &PlayerInput::default(), reload_ticks);
+    assert!(!weapon(&mut app).is_reloading());
+    assert_eq!(weapon(&mut app).ammo, Weapon::rifle().magazine_size);
+}
+
+#[test]
+fn reloading_blocks_firing_until_it_is_done() {
+    let (mut app, _) = weapon_app(Weapon { ammo: 10, ..Weapon::rifle() }, 10.0);
+    run_ticks(&mut app, &PlayerInput { reload: true, ..default() }, 1);
+    assert!(weapon(&mut app).is_reloading());
+
+    run_ticks(&mut app, &fire(), TICK_RATE as usize);
+    assert!(hits(&app).is_empty());
+
+    let reload_ticks = (Weapon::rifle().reload_time * TICK_RATE as f32) as usize;
+    run_ticks(&mut app, &fire(), reload_ticks);
+    assert!(!hits(&app).is_empty());
+    assert!(weapon(&mut app).ammo < Weapon::rifle().magazine_size);
+}
+
+#[test]
+fn demo_playback_does_not_fire() {
+    let (mut app, _) = weapon_app(Weapon::rifle(), 10.0);
+    app.insert_resource(DemoRecording::default());
+    run_ticks(&mut app, &PlayerInput::default(), 1);
+    let mut demo = app.world_mut().remove_resource::<DemoRecording>().unwrap().demo.unwrap();
+    demo.ticks[0] = fire();
+
+    app.insert_resource(DemoPlayback::new(demo));
+    run_ticks(&mut app, &PlayerInput::default(), 1);
+
+    assert!(hits(&app).is_empty(), "hit {:?} during playback", hits(&app));
+    assert_eq!(weapon(&mut app).ammo, Weapon::rifle().magazine_size);
+}